@@ -0,0 +1,375 @@
+//! Timing each day's parse and parts, and comparing against a saved baseline.
+//!
+//! Allocations are only counted when the binary uses `CountingAllocator` as
+//! its global allocator, as `aoc` does. Anywhere else they read as 0.
+
+use day_01::{process_frequency_deltas, Budget, Day01, SeenChoice, SeenStore};
+use day_02::Day02;
+use day_03::Day03;
+use error::{Diagnostics, Error, Result};
+use json::Json;
+use report::nanoseconds;
+use runner;
+use solution::Solution;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, counting every allocation made through it.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Allocations made so far by the whole program.
+pub fn allocations() -> usize {
+    ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+/// Timings for one stage of one day: `parse`, `a` or `b`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measurement {
+    pub day: u8,
+    pub stage: String,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+    /// Fewest allocations seen in any one iteration.
+    pub allocations: usize,
+}
+
+/// Run `stage` `iterations` times, timing each run.
+fn measure<F>(day: u8, stage: &str, iterations: usize, mut run: F) -> Result<Measurement>
+where
+    F: FnMut() -> Result<()>,
+{
+    let mut times = Vec::with_capacity(iterations);
+    let mut allocations = usize::MAX;
+
+    for _ in 0..iterations.max(1) {
+        let allocations_before = self::allocations();
+        let start = Instant::now();
+        run()?;
+        times.push(start.elapsed());
+        allocations = allocations.min(self::allocations() - allocations_before);
+    }
+
+    times.sort();
+
+    Ok(Measurement {
+        day,
+        stage: stage.to_string(),
+        min: times[0],
+        median: times[(times.len() - 1) / 2],
+        max: times[times.len() - 1],
+        allocations,
+    })
+}
+
+/// Time parsing and each part separately over the same input.
+pub fn bench_solution<S: Solution>(input: &[u8], iterations: usize) -> Result<Vec<Measurement>> {
+    let parse = measure(S::DAY, "parse", iterations, || {
+        black_box(S::parse(input, &mut Diagnostics::strict())?);
+        Ok(())
+    })?;
+
+    let parsed = S::parse(input, &mut Diagnostics::strict())?;
+    let part1 = measure(S::DAY, "a", iterations, || {
+        black_box(S::part1(&parsed)?);
+        Ok(())
+    })?;
+    let part2 = measure(S::DAY, "b", iterations, || {
+        black_box(S::part2(&parsed)?);
+        Ok(())
+    })?;
+
+    Ok(vec![parse, part1, part2])
+}
+
+/// Day 1 also loops over the changes for part 2, to time each store of
+/// seen frequencies against the other.
+pub fn bench_day_01(input: &[u8], iterations: usize) -> Result<Vec<Measurement>> {
+    let mut measurements = bench_solution::<Day01>(input, iterations)?;
+
+    let frequency_deltas = Day01::parse(input, &mut Diagnostics::strict())?;
+    for &(stage, choice) in &[("b-hash", SeenChoice::Hash), ("b-bits", SeenChoice::Bitmap)] {
+        measurements.push(measure(Day01::DAY, stage, iterations, || {
+            let mut seen_frequencies = SeenStore::new(&frequency_deltas, 0, choice);
+            black_box(process_frequency_deltas(
+                &frequency_deltas,
+                0,
+                &mut seen_frequencies,
+                &Budget::default(),
+            )?);
+            Ok(())
+        })?);
+    }
+
+    Ok(measurements)
+}
+
+/// A day that can be benchmarked.
+pub struct Bench {
+    pub day: u8,
+    pub run: fn(&[u8], usize) -> Result<Vec<Measurement>>,
+}
+
+/// Every day the benchmarks know about, in order.
+pub const BENCHES: &[Bench] = &[
+    Bench {
+        day: Day01::DAY,
+        run: bench_day_01,
+    },
+    Bench {
+        day: Day02::DAY,
+        run: bench_solution::<Day02>,
+    },
+    Bench {
+        day: Day03::DAY,
+        run: bench_solution::<Day03>,
+    },
+];
+
+/// Days to bench, picked the same way as the runner picks puzzles.
+/// Either part of a day selects the whole day.
+pub fn select(selector: &str) -> ::std::result::Result<Vec<&'static Bench>, String> {
+    let puzzles = runner::select(selector)?;
+    Ok(BENCHES
+        .iter()
+        .filter(|bench| puzzles.iter().any(|puzzle| puzzle.day == bench.day))
+        .collect())
+}
+
+impl Measurement {
+    pub fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("day".to_string(), Json::from(self.day)),
+            ("stage".to_string(), Json::from(self.stage.as_str())),
+            ("min_ns".to_string(), Json::from(nanoseconds(self.min))),
+            (
+                "median_ns".to_string(),
+                Json::from(nanoseconds(self.median)),
+            ),
+            ("max_ns".to_string(), Json::from(nanoseconds(self.max))),
+            ("allocations".to_string(), Json::from(self.allocations)),
+        ])
+    }
+
+    pub fn from_json(value: &Json) -> Result<Measurement> {
+        let number = |key: &str| {
+            value
+                .get(key)
+                .and_then(Json::as_f64)
+                .ok_or_else(|| Error::parse(1, 1, &value.to_string(), format!("missing '{}'", key)))
+        };
+        let duration = |key: &str| number(key).map(|ns| Duration::from_nanos(ns as u64));
+        let stage = value
+            .get("stage")
+            .and_then(Json::as_str)
+            .ok_or_else(|| Error::parse(1, 1, &value.to_string(), "missing 'stage'"))?;
+
+        Ok(Measurement {
+            day: number("day")? as u8,
+            stage: stage.to_string(),
+            min: duration("min_ns")?,
+            median: duration("median_ns")?,
+            max: duration("max_ns")?,
+            allocations: number("allocations")? as usize,
+        })
+    }
+}
+
+/// The baseline file: `{"benchmarks": [measurement, ...]}`.
+pub fn to_json(measurements: &[Measurement]) -> Json {
+    Json::Object(vec![(
+        "benchmarks".to_string(),
+        Json::Array(measurements.iter().map(Measurement::to_json).collect()),
+    )])
+}
+
+pub fn from_json(value: &Json) -> Result<Vec<Measurement>> {
+    value
+        .get("benchmarks")
+        .and_then(Json::as_array)
+        .ok_or_else(|| Error::parse(1, 1, "", "expected a 'benchmarks' array"))?
+        .iter()
+        .map(Measurement::from_json)
+        .collect()
+}
+
+/// A measurement next to the baseline for the same day and stage.
+pub struct Comparison {
+    pub measurement: Measurement,
+    pub baseline: Option<Measurement>,
+    pub regressed: bool,
+}
+
+impl Comparison {
+    /// Median time relative to the baseline, as a percentage change.
+    pub fn change_percent(&self) -> Option<f64> {
+        self.baseline.as_ref().map(|baseline| {
+            let before = nanoseconds(baseline.median).max(1) as f64;
+            let after = nanoseconds(self.measurement.median) as f64;
+            (after - before) / before * 100.0
+        })
+    }
+}
+
+/// Flag a regression when the median is more than `threshold_percent`
+/// slower than the baseline, or more allocations are made.
+pub fn compare(
+    measurements: &[Measurement],
+    baseline: &[Measurement],
+    threshold_percent: f64,
+) -> Vec<Comparison> {
+    measurements
+        .iter()
+        .map(|measurement| {
+            let baseline = baseline
+                .iter()
+                .find(|b| b.day == measurement.day && b.stage == measurement.stage)
+                .cloned();
+
+            let mut comparison = Comparison {
+                measurement: measurement.clone(),
+                baseline,
+                regressed: false,
+            };
+
+            comparison.regressed = match comparison.baseline {
+                Some(ref baseline) => {
+                    comparison.change_percent().unwrap_or(0.0) > threshold_percent
+                        || measurement.allocations > baseline.allocations
+                }
+                None => false,
+            };
+
+            comparison
+        })
+        .collect()
+}
+
+/// Lay the comparisons out one stage per row under a header.
+pub fn format_table(comparisons: &[Comparison]) -> String {
+    let row = |columns: [&str; 7]| {
+        format!(
+            "{:<5}{:<7}{:>12}{:>12}{:>12}{:>8}  {}\n",
+            columns[0], columns[1], columns[2], columns[3], columns[4], columns[5], columns[6]
+        )
+    };
+
+    let mut table = row(["Day", "Stage", "Min", "Median", "Max", "Allocs", "Baseline"]);
+
+    for comparison in comparisons {
+        let measurement = &comparison.measurement;
+        let baseline = match comparison.change_percent() {
+            Some(change) if comparison.regressed => format!("{:+.1}% REGRESSION", change),
+            Some(change) => format!("{:+.1}%", change),
+            None => "-".to_string(),
+        };
+
+        table += &row([
+            &format!("{:02}", measurement.day),
+            &measurement.stage,
+            &format!("{:.1?}", measurement.min),
+            &format!("{:.1?}", measurement.median),
+            &format!("{:.1?}", measurement.max),
+            &measurement.allocations.to_string(),
+            &baseline,
+        ]);
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use json;
+
+    fn measurement(stage: &str, median_ms: u64, allocations: usize) -> Measurement {
+        Measurement {
+            day: 1,
+            stage: stage.to_string(),
+            min: Duration::from_millis(median_ms - 1),
+            median: Duration::from_millis(median_ms),
+            max: Duration::from_millis(median_ms + 1),
+            allocations,
+        }
+    }
+
+    #[test]
+    fn test_bench_solution() {
+        let measurements = bench_solution::<Day01>(b"+3\n+3\n+4\n-2\n-4\n", 5).unwrap();
+        let stages: Vec<&str> = measurements.iter().map(|m| m.stage.as_str()).collect();
+        assert_eq!(stages, vec!["parse", "a", "b"]);
+
+        for measurement in &measurements {
+            assert!(measurement.min <= measurement.median);
+            assert!(measurement.median <= measurement.max);
+        }
+    }
+
+    #[test]
+    fn test_bench_day_01() {
+        let measurements = bench_day_01(b"+3\n+3\n+4\n-2\n-4\n", 5).unwrap();
+        let stages: Vec<&str> = measurements.iter().map(|m| m.stage.as_str()).collect();
+        assert_eq!(stages, vec!["parse", "a", "b", "b-hash", "b-bits"]);
+    }
+
+    #[test]
+    fn test_bench_bad_input() {
+        assert!(bench_solution::<Day01>(b"+3\nthree\n", 5).is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let days: Vec<u8> = select("2b").unwrap().iter().map(|b| b.day).collect();
+        assert_eq!(days, vec![2]);
+        assert_eq!(select("all").unwrap().len(), BENCHES.len());
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let measurements = vec![measurement("parse", 10, 3), measurement("a", 2, 0)];
+        let text = to_json(&measurements).to_string();
+        let read = from_json(&json::parse(&text).unwrap()).unwrap();
+        assert_eq!(read, measurements);
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = vec![measurement("parse", 10, 3), measurement("a", 10, 0)];
+        let current = vec![
+            measurement("parse", 10, 4),
+            measurement("a", 12, 0),
+            measurement("b", 100, 0),
+        ];
+
+        let comparisons = compare(&current, &baseline, 10.0);
+        let regressed: Vec<bool> = comparisons.iter().map(|c| c.regressed).collect();
+
+        // More allocations, 20% slower, and nothing to compare with
+        assert_eq!(regressed, vec![true, true, false]);
+        assert_eq!(comparisons[1].change_percent().map(f64::round), Some(20.0));
+
+        let comparisons = compare(&current, &baseline, 25.0);
+        assert!(!comparisons[1].regressed);
+    }
+}
@@ -0,0 +1,356 @@
+//! Whole numbers of any size, for sums that outgrow the built in integers.
+//!
+//! Only what adding up frequencies needs: parsing, printing, comparing,
+//! adding and the remainder. Stored as a sign and base 2^32 digits.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Neg};
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    /// Never set for 0, so each number has the one form.
+    negative: bool,
+    /// Least significant first, with no zeros on the end. Empty for 0.
+    magnitude: Vec<u32>,
+}
+
+fn trim(mut magnitude: Vec<u32>) -> Vec<u32> {
+    while magnitude.last() == Some(&0) {
+        magnitude.pop();
+    }
+    magnitude
+}
+
+fn compare_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut sum = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+
+    for i in 0..a.len().max(b.len()) {
+        let digit = u64::from(*a.get(i).unwrap_or(&0)) + u64::from(*b.get(i).unwrap_or(&0)) + carry;
+        sum.push(digit as u32);
+        carry = digit >> 32;
+    }
+    sum.push(carry as u32);
+
+    trim(sum)
+}
+
+/// `a - b`, where `a` is at least `b`.
+fn subtract_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+
+    for (i, digit) in a.iter().enumerate() {
+        let taken = i64::from(*b.get(i).unwrap_or(&0)) + borrow;
+        let mut digit = i64::from(*digit) - taken;
+        borrow = 0;
+        if digit < 0 {
+            digit += 1 << 32;
+            borrow = 1;
+        }
+        difference.push(digit as u32);
+    }
+
+    trim(difference)
+}
+
+/// `magnitude * factor + addend`, in place.
+fn multiply_add_small(magnitude: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = u64::from(addend);
+    for digit in magnitude.iter_mut() {
+        let product = u64::from(*digit) * u64::from(factor) + carry;
+        *digit = product as u32;
+        carry = product >> 32;
+    }
+    if carry > 0 {
+        magnitude.push(carry as u32);
+    }
+}
+
+/// Divide in place by `divisor`, giving back the remainder.
+fn divide_small(magnitude: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut remainder = 0u64;
+    for digit in magnitude.iter_mut().rev() {
+        let current = (remainder << 32) | u64::from(*digit);
+        *digit = (current / u64::from(divisor)) as u32;
+        remainder = current % u64::from(divisor);
+    }
+    while magnitude.last() == Some(&0) {
+        magnitude.pop();
+    }
+    remainder as u32
+}
+
+impl BigInt {
+    pub fn zero() -> BigInt {
+        BigInt::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    fn from_parts(negative: bool, magnitude: Vec<u32>) -> BigInt {
+        let magnitude = trim(magnitude);
+        BigInt {
+            negative: negative && !magnitude.is_empty(),
+            magnitude,
+        }
+    }
+
+    /// `self` mod `modulus`, from 0 up to `|modulus|` whatever the signs,
+    /// like `i64::rem_euclid`. Panics if `modulus` is 0.
+    pub fn rem_euclid(&self, modulus: &BigInt) -> BigInt {
+        assert!(!modulus.is_zero(), "remainder by zero");
+
+        // Long division a bit at a time, keeping only the remainder
+        let mut remainder: Vec<u32> = Vec::new();
+        for digit in self.magnitude.iter().rev() {
+            for bit in (0..32).rev() {
+                multiply_add_small(&mut remainder, 2, (digit >> bit) & 1);
+                if compare_magnitudes(&remainder, &modulus.magnitude) != Ordering::Less {
+                    remainder = subtract_magnitudes(&remainder, &modulus.magnitude);
+                }
+            }
+        }
+
+        let remainder = trim(remainder);
+        if self.negative && !remainder.is_empty() {
+            BigInt::from_parts(false, subtract_magnitudes(&modulus.magnitude, &remainder))
+        } else {
+            BigInt::from_parts(false, remainder)
+        }
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> BigInt {
+        BigInt::from(i128::from(value))
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> BigInt {
+        let mut magnitude = Vec::new();
+        let mut rest = value.unsigned_abs();
+        while rest > 0 {
+            magnitude.push(rest as u32);
+            rest >>= 32;
+        }
+        BigInt::from_parts(value < 0, magnitude)
+    }
+}
+
+impl<'a> Add<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(
+                self.negative,
+                add_magnitudes(&self.magnitude, &other.magnitude),
+            );
+        }
+
+        // Signs differ, so the larger magnitude wins
+        match compare_magnitudes(&self.magnitude, &other.magnitude) {
+            Ordering::Less => BigInt::from_parts(
+                other.negative,
+                subtract_magnitudes(&other.magnitude, &self.magnitude),
+            ),
+            _ => BigInt::from_parts(
+                self.negative,
+                subtract_magnitudes(&self.magnitude, &other.magnitude),
+            ),
+        }
+    }
+}
+
+impl Add for BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: BigInt) -> BigInt {
+        &self + &other
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitudes(&self.magnitude, &other.magnitude),
+            (true, true) => compare_magnitudes(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Worded as for the built in integers, so bad lines read the same.
+#[derive(Debug, PartialEq)]
+pub enum ParseBigIntError {
+    Empty,
+    InvalidDigit,
+}
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseBigIntError::Empty => write!(f, "cannot parse integer from empty string"),
+            ParseBigIntError::InvalidDigit => write!(f, "invalid digit found in string"),
+        }
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    /// Decimal digits, with an optional `+` or `-` in front.
+    fn from_str(text: &str) -> Result<BigInt, ParseBigIntError> {
+        let (negative, digits) = match text.as_bytes().first() {
+            Some(b'-') => (true, &text[1..]),
+            Some(b'+') => (false, &text[1..]),
+            Some(_) => (false, text),
+            None => return Err(ParseBigIntError::Empty),
+        };
+        if digits.is_empty() {
+            return Err(ParseBigIntError::InvalidDigit);
+        }
+
+        let mut magnitude = Vec::new();
+        for digit in digits.chars() {
+            let digit = digit.to_digit(10).ok_or(ParseBigIntError::InvalidDigit)?;
+            multiply_add_small(&mut magnitude, 10, digit);
+        }
+
+        Ok(BigInt::from_parts(negative, magnitude))
+    }
+}
+
+impl fmt::Display for BigInt {
+    /// Honours `{:+}` and widths, as the built in integers do.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Nine decimal digits at a time, least significant first
+        let mut rest = self.magnitude.clone();
+        let mut chunks = Vec::new();
+        while !rest.is_empty() {
+            chunks.push(divide_small(&mut rest, 1_000_000_000));
+        }
+
+        let mut digits = match chunks.pop() {
+            Some(chunk) => chunk.to_string(),
+            None => "0".to_string(),
+        };
+        for chunk in chunks.iter().rev() {
+            digits += &format!("{:09}", chunk);
+        }
+
+        f.pad_integral(!self.negative, "", &digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(text: &str) -> BigInt {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let huge = "-340282366920938463463374607431768211456123";
+        assert_eq!(big(huge).to_string(), huge);
+        assert_eq!(big("+0007").to_string(), "7");
+        assert_eq!(big("-0").to_string(), "0");
+        assert_eq!(format!("{:+}", big("12")), "+12");
+        assert_eq!(BigInt::from(i128::MIN).to_string(), i128::MIN.to_string());
+
+        assert_eq!("".parse::<BigInt>(), Err(ParseBigIntError::Empty));
+        assert_eq!("-".parse::<BigInt>(), Err(ParseBigIntError::InvalidDigit));
+        assert_eq!("1x".parse::<BigInt>(), Err(ParseBigIntError::InvalidDigit));
+    }
+
+    #[test]
+    fn test_add() {
+        let max = BigInt::from(i128::MAX);
+        assert_eq!(
+            (&max + &max).to_string(),
+            "340282366920938463463374607431768211454"
+        );
+        assert_eq!(big("5") + big("-8"), big("-3"));
+        assert_eq!(big("-5") + big("8"), big("3"));
+        assert_eq!(big("-5") + big("5"), BigInt::zero());
+        assert_eq!(-(max.clone()) + max, BigInt::zero());
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut numbers = vec![
+            big("3"),
+            big("-10"),
+            big("0"),
+            big("-2"),
+            big("99999999999999"),
+        ];
+        numbers.sort();
+        assert_eq!(
+            numbers,
+            vec![
+                big("-10"),
+                big("-2"),
+                big("0"),
+                big("3"),
+                big("99999999999999")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rem_euclid() {
+        for (value, modulus) in &[
+            (17i128, 5i128),
+            (-17, 5),
+            (17, -5),
+            (-17, -5),
+            (-15, 5),
+            (3, 7),
+        ] {
+            assert_eq!(
+                BigInt::from(*value).rem_euclid(&BigInt::from(*modulus)),
+                BigInt::from(value.rem_euclid(*modulus)),
+                "{} mod {}",
+                value,
+                modulus
+            );
+        }
+
+        let large = big("-100000000000000000000000000001");
+        assert_eq!(large.rem_euclid(&big("1000000007")), big("34299"));
+    }
+}
@@ -0,0 +1,365 @@
+extern crate advent_2018;
+
+use advent_2018::bench::{self, CountingAllocator, Measurement};
+use advent_2018::cli::{self, Args};
+use advent_2018::error::{Diagnostics, Mode};
+use advent_2018::fetch::{self, Fetched, Site};
+use advent_2018::http;
+use advent_2018::json;
+use advent_2018::readme;
+use advent_2018::report::{self, Format, Report};
+use advent_2018::runner::{self, Puzzle};
+use advent_2018::scaffold;
+use advent_2018::submit;
+use advent_2018::verify;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+// Lets benchmarks count allocations
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const USAGE: &str = "Usage:
+  aoc run <1a|1b|1|all> [input file] [--lenient] [--format text|json]
+  aoc verify [1a|1b|1|all]
+  aoc bench [1|all] [--iterations N] [--save file] [--baseline file] [--threshold percent]
+  aoc fetch <day> [--session token] [--base-url url]
+  aoc submit <day> <a|b> [--session token] [--base-url url]
+  aoc new <day>
+  aoc readme [--check] [--timings [--iterations N]]
+
+Every command also takes --verbosity quiet|normal|trace (default normal)
+and --log file, which sends that output to the file instead of stderr.
+
+Input defaults to input/day_XXa/input.txt for each day.
+Bad input lines stop the run unless --lenient is given, in which case they
+are skipped and listed after the answers. --format json prints the day,
+part, answer, timings and skipped lines of each puzzle as one JSON document.
+Verify checks answers against input/day_XXa/answers.txt.
+Bench times parsing and each part over 10 iterations by default. --save
+writes the timings as JSON, and --baseline compares against a saved file,
+failing if the median is over --threshold percent (default 10) slower or
+more allocations are made.
+Fetch downloads a day's input to input/day_XXa/input.txt unless it is
+already there. The session token is the session cookie of a logged in
+browser, from --session or AOC_SESSION. The site is https://adventofcode.com
+unless --base-url or AOC_BASE_URL say otherwise.
+Submit solves a part on the checked in input and posts the answer, using
+the same session and site. Replies are kept in input/submissions.txt, and
+answers already given, or ruled out by an earlier too high or too low, are
+not sent again. Nor is anything sent while the site has asked to wait.
+New sets up a day: its module, a binary for each part, the input directory
+and README section, registered with run, verify and bench.
+Readme rebuilds the README's contents and day sections from the registered
+puzzles, or with --check fails if they are out of date. --timings also
+rebuilds the timings table from a benchmark.";
+
+/// Solve the selected puzzles and print their answers as a table, or JSON.
+fn run(
+    selector: &str,
+    input_path: Option<PathBuf>,
+    mode: Mode,
+    format: Format,
+) -> Result<(), String> {
+    let mut reports = Vec::new();
+
+    for puzzle in runner::select(selector)? {
+        let path = input_path
+            .clone()
+            .unwrap_or_else(|| puzzle.default_input_path());
+        let report = Report::solve_file(puzzle, &path, mode);
+
+        // The table stops at the first failure, JSON reports it and carries on
+        if format == Format::Text {
+            if let Err(ref e) = report.result {
+                return Err(format!("{} {}: {}", puzzle.name(), path.display(), e));
+            }
+        }
+
+        reports.push((path, report));
+    }
+
+    if format == Format::Json {
+        let reports: Vec<Report> = reports.into_iter().map(|(_, report)| report).collect();
+        println!("{}", report::to_json(&reports));
+
+        let failed = reports.iter().filter(|r| r.result.is_err()).count();
+        if failed > 0 {
+            return Err(format!("{} of {} failed", failed, reports.len()));
+        }
+        return Ok(());
+    }
+
+    let answers: Vec<(&Puzzle, String)> = reports
+        .iter()
+        .filter_map(|(_, report)| {
+            let answer = report.result.as_ref().ok()?.answer.clone();
+            Some((report.puzzle, answer))
+        })
+        .collect();
+    print!("{}", runner::format_table(&answers));
+
+    for (path, report) in &reports {
+        for error in &report.diagnostics {
+            eprintln!("{} {}: {}", report.puzzle.name(), path.display(), error);
+        }
+    }
+
+    Ok(())
+}
+
+/// Check the selected puzzles against their known answers.
+fn verify(selector: &str) -> Result<(), String> {
+    let verifications: Vec<verify::Verification> = runner::select(selector)?
+        .into_iter()
+        .map(|puzzle| verify::verify(puzzle, Path::new(".")))
+        .collect();
+
+    print!("{}", verify::format_table(&verifications));
+
+    let failed = verifications.iter().filter(|v| !v.is_ok()).count();
+    if failed > 0 {
+        return Err(format!("{} of {} failed", failed, verifications.len()));
+    }
+
+    Ok(())
+}
+
+/// Time the selected days on their checked in input, `--iterations` times.
+fn measure(selector: &str, args: &Args) -> Result<Vec<Measurement>, String> {
+    let iterations = args.parsed::<usize>("iterations")?.unwrap_or(10);
+
+    let mut measurements = Vec::new();
+    for day in bench::select(selector)? {
+        let path = runner::default_input_path(day.day);
+
+        // A day fresh from `aoc new` has no input to time yet
+        if !path.exists() {
+            eprintln!("Skipping day {}: no input at {}", day.day, path.display());
+            continue;
+        }
+
+        let input =
+            fs::read(&path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        let timed =
+            (day.run)(&input, iterations).map_err(|e| format!("{}: {}", path.display(), e))?;
+        measurements.extend(timed);
+    }
+
+    Ok(measurements)
+}
+
+/// Compare the selected days against a baseline, and maybe save a new one.
+fn bench(selector: &str, args: &Args) -> Result<(), String> {
+    let threshold = args.parsed::<f64>("threshold")?.unwrap_or(10.0);
+    let measurements = measure(selector, args)?;
+
+    let baseline = match args.option("baseline") {
+        Some(path) => {
+            let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+            json::parse(&text)
+                .and_then(|value| bench::from_json(&value))
+                .map_err(|e| format!("{}: {}", path, e))?
+        }
+        None => Vec::new(),
+    };
+
+    let comparisons = bench::compare(&measurements, &baseline, threshold);
+    print!("{}", bench::format_table(&comparisons));
+
+    if let Some(path) = args.option("save") {
+        fs::write(path, format!("{}\n", bench::to_json(&measurements)))
+            .map_err(|e| format!("{}: {}", path, e))?;
+    }
+
+    let regressions = comparisons.iter().filter(|c| c.regressed).count();
+    if regressions > 0 {
+        return Err(format!("{} regressions against the baseline", regressions));
+    }
+
+    Ok(())
+}
+
+fn parse_day(day: &str) -> Result<u8, String> {
+    match day.parse::<u8>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(format!("'{}' is not a day from 1 to 25", day)),
+    }
+}
+
+/// Download a day's input, unless it is already here.
+fn fetch(day: &str, args: &Args) -> Result<(), String> {
+    let day = parse_day(day)?;
+
+    let site = site(args);
+    let client = http::client_for(&site.base_url).map_err(|e| e.to_string())?;
+    match fetch::fetch(day, &site, client.as_ref(), Path::new(".")) {
+        Ok(Fetched::Cached(path)) => println!("Already have {}", path.display()),
+        Ok(Fetched::Downloaded(path)) => println!("Downloaded {}", path.display()),
+        Err(e) => return Err(format!("Day {}: {}", day, e)),
+    }
+
+    Ok(())
+}
+
+/// Where to find the site and who to be, from the environment and `args`.
+fn site(args: &Args) -> Site {
+    let mut site = Site::from_env();
+    if let Some(base_url) = args.option("base-url") {
+        site.base_url = base_url.to_string();
+    }
+    if let Some(session) = args.option("session") {
+        site.session = Some(session.to_string());
+    }
+    site
+}
+
+/// Solve one part on the checked in input and post the answer.
+fn submit(day: &str, part: &str, args: &Args) -> Result<(), String> {
+    let puzzles = runner::select(&format!("{}{}", day, part))?;
+    let puzzle = match puzzles[..] {
+        [puzzle] => puzzle,
+        _ => return Err(format!("Pick one part to submit, not '{}'", part)),
+    };
+
+    let path = puzzle.default_input_path();
+    let answer = puzzle
+        .solve_file(&path, &mut Diagnostics::strict())
+        .map_err(|e| format!("{} {}: {}", puzzle.name(), path.display(), e))?
+        .answer;
+
+    let site = site(args);
+    let client = http::client_for(&site.base_url).map_err(|e| e.to_string())?;
+    let submission = submit::submit(
+        puzzle.day,
+        puzzle.part,
+        &answer,
+        &site,
+        client.as_ref(),
+        &submit::default_history_path(),
+        submit::now(),
+    )
+    .map_err(|e| format!("{}: {}", puzzle.name(), e))?;
+
+    println!("{} {}: {}", puzzle.name(), answer, submission);
+    Ok(())
+}
+
+/// Set up the files for a new day.
+fn new_day(day: &str) -> Result<(), String> {
+    let day = parse_day(day)?;
+    let written =
+        scaffold::scaffold(day, Path::new(".")).map_err(|e| format!("Day {}: {}", day, e))?;
+
+    for path in written {
+        println!("{}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Rebuild the generated parts of the README, or check they are up to date.
+fn readme(args: &Args) -> Result<(), String> {
+    let path = Path::new("README.md");
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut generated = readme::generate(&text, &readme::registered_days(), Path::new("."))?;
+
+    if args.flag("check") {
+        if generated != text {
+            return Err("README.md is out of date, run `aoc readme`".to_string());
+        }
+        println!("README.md is up to date");
+        return Ok(());
+    }
+
+    if args.flag("timings") {
+        let table = readme::timings_table(&measure("all", args)?);
+        generated = readme::replace_block(&generated, "timings", &table)?;
+    }
+
+    if generated == text {
+        println!("README.md is up to date");
+    } else {
+        fs::write(path, generated).map_err(|e| format!("{}: {}", path.display(), e))?;
+        println!("README.md updated");
+    }
+
+    Ok(())
+}
+
+/// Fail on anything but `known` and the trace options, then set up tracing.
+fn check_options(args: &Args, known: &[&str]) -> Result<(), String> {
+    let known: Vec<&str> = known.iter().chain(cli::TRACE_OPTIONS).cloned().collect();
+    args.expect_only(&known)?;
+    cli::apply_trace_options(args)
+}
+
+fn dispatch(args: &Args) -> Result<(), String> {
+    let selector = args.positional(1);
+
+    match (args.positional(0), args.positionals().len()) {
+        (Some("run"), 2) | (Some("run"), 3) => {
+            check_options(args, &["lenient", "format"])?;
+            let mode = if args.flag("lenient") {
+                Mode::Lenient
+            } else {
+                Mode::Strict
+            };
+            run(
+                selector.unwrap(),
+                args.positional(2).map(PathBuf::from),
+                mode,
+                args.parsed::<Format>("format")?.unwrap_or(Format::Text),
+            )
+        }
+        (Some("verify"), 1) | (Some("verify"), 2) => {
+            check_options(args, &[])?;
+            verify(selector.unwrap_or("all"))
+        }
+        (Some("bench"), 1) | (Some("bench"), 2) => {
+            check_options(args, &["iterations", "save", "baseline", "threshold"])?;
+            bench(selector.unwrap_or("all"), args)
+        }
+        (Some("fetch"), 2) => {
+            check_options(args, &["session", "base-url"])?;
+            fetch(selector.unwrap(), args)
+        }
+        (Some("submit"), 3) => {
+            check_options(args, &["session", "base-url"])?;
+            submit(selector.unwrap(), args.positional(2).unwrap(), args)
+        }
+        (Some("readme"), 1) => {
+            check_options(args, &["check", "timings", "iterations"])?;
+            readme(args)
+        }
+        (Some("new"), 2) => {
+            check_options(args, &[])?;
+            new_day(selector.unwrap())
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn main() {
+    let args = Args::parse(
+        env::args().skip(1),
+        &[
+            "iterations",
+            "save",
+            "baseline",
+            "threshold",
+            "format",
+            "session",
+            "base-url",
+            "verbosity",
+            "log",
+        ],
+    );
+
+    if let Err(message) = args.and_then(|args| dispatch(&args)) {
+        eprintln!("{}", message);
+        process::exit(1);
+    }
+}
@@ -1,100 +1,78 @@
-use std::io::{self, BufRead, Write};
-
-#[cfg(test)]
-use std::io::BufReader;
-
-#[cfg(any(unix))]
-const EOF_CHARACTER_COUNT: usize = 1;
-
-#[cfg(any(windows))]
-const EOF_CHARACTER_COUNT: usize = 2;
-
-/// Solution to day 1 part 1
-pub fn day_01a<T: BufRead>(mut handle: T, start_frequency: i32) -> i32 {
-    // Reusable lock to write to stdout via writelin - perf gains over println macro
-    let stdout = io::stdout();
-    let mut output_handle = stdout.lock();
-
-    // Allow user to stipulate start frequency rather than assuming zero.AsMut
-    let mut current_frequency = start_frequency;
-
-    // Give large capacity for the line. 32 should be plenty.
-    let mut input = String::with_capacity(32);
-
-    // do something with line
-    while handle.read_line(&mut input).expect("Failed to read line") > 0 {
-        // Empty lines end the program
-        if input.len() == 0 || input == "\n" {
-            break;
-        }
-
-        // Read line includes line endings - just parse the content
-        // Using slice that this shouldn't create an allocation ... ?
-        match input[0..input.len() - EOF_CHARACTER_COUNT].parse::<i32>() {
-            // Parsing succeded - process frequency
-            Ok(frequency_delta) => {
-                let previous_frequency = current_frequency;
-                current_frequency += frequency_delta;
-
-                writeln!(
-                    output_handle,
-                    "  - Current frequency {}, change of {:+}; resulting frequency {}.",
-                    previous_frequency, frequency_delta, current_frequency
-                ).unwrap();
-            }
-            // Parsing failed, report error but continue processing stream
-            Err(e) => {
-                writeln!(output_handle, "ERROR: Input not integer. {}", e).unwrap();
+extern crate advent_2018;
+
+use advent_2018::cli::DayOptions;
+use advent_2018::day_01::{
+    check_stream_format, frequency_stream, print_frequency_stream, read_frequency_sequences,
+    single_sequence_input, start_option, sum_frequency_deltas,
+};
+use advent_2018::error::Diagnostics;
+use advent_2018::report::{self, Format};
+use advent_2018::trace;
+use std::io;
+use std::process;
+
+/// Enter data to test via stdin, or a file, for this day's exercise.
+/// Each sequence is solved on its own. `stream` gives the running total
+/// as each change is read instead, for input that may not end.
+fn main() -> io::Result<()> {
+    let (options, start_frequency) = DayOptions::from_env_with_commands(
+        "day_01a",
+        &["stream"],
+        &["start"],
+        "[--start frequency]",
+        |args| {
+            check_stream_format(args)?;
+            start_option(args)
+        },
+    );
+    if options.format == Format::Json {
+        match single_sequence_input(options.open_input()) {
+            Ok(input) => report::print_json("1a", &input[..]),
+            Err(message) => {
+                eprintln!("ERROR: {}", message);
+                process::exit(1);
             }
         }
-
-        // Re-using buffer rather than allocating new string each read.
-        input.clear();
+        return Ok(());
     }
 
-    current_frequency
-}
-
-/// Enter data to test via stdin for this day's exercise.
-fn main() -> io::Result<()> {
-    println!("Day 01: https://adventofcode.com/2018/day/1");
+    trace::info(format_args!("Day 01: https://adventofcode.com/2018/day/1"));
 
-    let stdin = io::stdin();
+    // Only the total is kept, however much is read
+    if options.command.is_some() {
+        let stream = frequency_stream(options.open_stream(), start_frequency);
+        if !print_frequency_stream(stream) {
+            process::exit(1);
+        }
+        return Ok(());
+    }
 
-    // Locking once for all reading.
-    let input_handle = stdin.lock();
+    let input_handle = options.open_input();
 
-    day_01a(input_handle, 0);
+    // Bad lines are skipped, then listed once the input is done
+    let mut diagnostics = Diagnostics::lenient();
+    let result = read_frequency_sequences(input_handle, &mut diagnostics);
+    let mut sequences = diagnostics.finish(result);
 
-    Ok(())
-}
-
-#[cfg(test)]
-mod tests {
-    // Note this useful idiom: importing names from outer (for mod tests) scope.
-    use super::*;
-
-    #[test]
-    fn test_day_01_example_01() {
-        // +1, +1, +1 results in 3
-        let input = BufReader::new("1\n1\n1\n\n".as_bytes());
-        let frequency = day_01a(input, 0);
-        assert_eq!(frequency, 3);
+    // Nothing at all still has a resulting frequency
+    if sequences.is_empty() {
+        sequences.push(Default::default());
     }
 
-    #[test]
-    fn test_day_01_example_02() {
-        // +1, +1, -2 results in 0
-        let input = BufReader::new("1\n1\n-2\n\n".as_bytes());
-        let frequency = day_01a(input, 0);
-        assert_eq!(frequency, 0);
+    let mut failed = false;
+    for sequence in &sequences {
+        match sum_frequency_deltas(sequence, start_frequency) {
+            Ok(frequency) => println!("Resulting frequency {}", frequency),
+            Err(error) => {
+                eprintln!("ERROR: {}", error);
+                failed = true;
+            }
+        }
     }
 
-    #[test]
-    fn test_day_01_example_03() {
-        // -1, -2, -3 results in -6
-        let input = BufReader::new("-1\n-2\n-3\n\n".as_bytes());
-        let frequency = day_01a(input, 0);
-        assert_eq!(frequency, -6);
+    if failed {
+        process::exit(1);
     }
+
+    Ok(())
 }
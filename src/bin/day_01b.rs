@@ -1,240 +1,184 @@
-//use std::error::Error;
-use std::collections::HashSet;
-use std::fmt;
-use std::io::{self, BufRead, Write};
-
-#[cfg(test)]
-use std::io::{Cursor, SeekFrom};
-
-#[cfg(any(unix))]
-const EOF_CHARACTER_COUNT: usize = 1;
-
-#[cfg(any(windows))]
-const EOF_CHARACTER_COUNT: usize = 2;
-
-#[derive(Debug)]
-pub struct FrequencyNotRepeated;
-
-/// Not found error state for when frequency is not repeated in an iteration
-impl fmt::Display for FrequencyNotRepeated {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Frequency not repeated in sequence")
-    }
-}
-
-/// Read all frequencies from standard in
-fn read_frequency_deltas<T: BufRead>(handle: &mut T, frequency_deltas: &mut Vec<i32>) {
-    // Reusable lock to write to stdout via writelin - perf gains over println macro
-    let stdout = io::stdout();
-    let mut output_handle = stdout.lock();
-
-    // Give large capacity for the line. 32 should be plenty.
-    let mut input = String::with_capacity(32);
-
-    // do something with line
-    while handle.read_line(&mut input).expect("Failed to read line") > 0 {
-        // Empty lines end the program
-        if input.len() == 0 || input == "\n" {
-            break;
-        }
-
-        // Read line includes line endings - just parse the content
-        // Using slice that this shouldn't create an allocation ... ?
-        match input[0..input.len() - EOF_CHARACTER_COUNT].parse::<i32>() {
-            // Parsing succeded - process frequency
-            Ok(frequency_delta) => {
-                frequency_deltas.push(frequency_delta);
-            }
-            // Parsing failed, report error but continue processing stream
-            Err(e) => {
-                writeln!(output_handle, "ERROR: Input not integer. {}", e).unwrap();
+extern crate advent_2018;
+
+use advent_2018::cli::DayOptions;
+use advent_2018::day_01::{
+    check_stream_format, count_sequences, day_01b, frequency_stream, nth_visit,
+    print_frequency_stream, process_frequency_deltas, read_frequency_sequences, repeats_within,
+    seen_option, single_sequence_input, start_option, stats_options, visit_histogram, Budget,
+    Repeat, SeenChoice, SeenFrequencies, SeenStore, Sequence, StatsOptions,
+};
+use advent_2018::error::{Diagnostics, Error};
+use advent_2018::report::{self, Format};
+use advent_2018::trace;
+use std::io::{self, BufRead};
+use std::process;
+
+/// Enter data to test via stdin, or a file, for this day's exercise.
+/// Each sequence is solved on its own. `stats` looks beyond the first
+/// repeat instead, and `stream` gives the first repeat as it happens, for
+/// input that may not end, without going round again. `--seen` keeps the
+/// changes and picks how the frequencies seen are kept instead.
+fn main() -> io::Result<()> {
+    let (options, (start_frequency, stats, seen)) = DayOptions::from_env_with_commands(
+        "day_01b",
+        &["stats", "stream"],
+        &["start", "visits", "cycles", "seen"],
+        "[--start frequency] [--visits count] [--cycles count] [--seen auto|bitmap|hash]",
+        |args| {
+            check_stream_format(args)?;
+            Ok((
+                start_option(args)?,
+                stats_options(args)?,
+                seen_option(args)?,
+            ))
+        },
+    );
+    if options.format == Format::Json {
+        match single_sequence_input(options.open_input()) {
+            Ok(input) => report::print_json("1b", &input[..]),
+            Err(message) => {
+                eprintln!("ERROR: {}", message);
+                process::exit(1);
             }
         }
-
-        // Re-using buffer rather than allocating new string each read.
-        input.clear();
+        return Ok(());
     }
-}
 
-/// Keep applying deltas until a repeat frequency is found
-fn process_frequency_deltas(
-    frequency_deltas: &Vec<i32>,
-    seen_frequencies: &mut HashSet<i32>,
-) -> i32 {
-    let mut current_frequency: i32 = 0;
-
-    loop {
-        for delta in frequency_deltas {
-            current_frequency = current_frequency + delta;
-            if seen_frequencies.contains(&current_frequency) {
-                return current_frequency;
-            }
+    trace::info(format_args!("Day 01: https://adventofcode.com/2018/day/1"));
 
-            seen_frequencies.insert(current_frequency);
+    if options.command.as_deref() == Some("stream") {
+        let stream = frequency_stream(options.open_stream(), start_frequency).watch_repeats();
+        if !print_frequency_stream(stream) {
+            process::exit(1);
         }
+        return Ok(());
     }
-}
 
-/// Original solution to day 1 part 2
-/// ... too hacky though.
-/// Would require reading file over and over again as designed.
-/// Okay for small values, but meh the actual input took way too long because
-/// * Reading files
-/// * Uses vector instead of map - and never removed dupes
-pub fn day_01b_old<T: BufRead>(
-    handle: &mut T,
-    frequency_values: &mut Vec<i32>,
-) -> Result<i32, FrequencyNotRepeated> {
-    // Reusable lock to write to stdout via writelin - perf gains over println macro
-    let stdout = io::stdout();
-    let mut output_handle = stdout.lock();
-
-    // Allow user to stipulate start frequency rather than assuming zero.AsMut
-    let mut current_frequency: i32 = *frequency_values.last().unwrap();
-
-    // Give large capacity for the line. 32 should be plenty.
-    let mut input = String::with_capacity(32);
-
-    // do something with line
-    while handle.read_line(&mut input).expect("Failed to read line") > 0 {
-        // Empty lines end the program
-        if input.len() == 0 || input == "\n" {
-            break;
-        }
+    let mut input_handle = options.open_input();
+    let mut failed = false;
 
-        // Read line includes line endings - just parse the content
-        // Using slice that this shouldn't create an allocation ... ?
-        match input[0..input.len() - EOF_CHARACTER_COUNT].parse::<i32>() {
-            // Parsing succeded - process frequency
-            Ok(frequency_delta) => {
-                current_frequency += frequency_delta;
-
-                writeln!(
-                    output_handle,
-                    "  - Current frequency {}, change of {:+}; resulting frequency {}.",
-                    frequency_values[frequency_values.len() - 1],
-                    frequency_delta,
-                    current_frequency
-                ).unwrap();
-
-                if frequency_values.contains(&current_frequency) {
-                    return Ok(current_frequency);
+    match (stats, seen) {
+        (Some(stats), _) => {
+            for sequence in &read_sequences(input_handle) {
+                if let Err(error) = print_stats(sequence, start_frequency, &stats) {
+                    eprintln!("ERROR: {}", error);
+                    failed = true;
                 }
-
-                frequency_values.push(current_frequency);
             }
-            // Parsing failed, report error but continue processing stream
-            Err(e) => {
-                writeln!(output_handle, "ERROR: Input not integer. {}", e).unwrap();
+        }
+        (None, Some(choice)) => {
+            for sequence in &read_sequences(input_handle) {
+                if let Err(error) = print_stepped_repeat(sequence, start_frequency, choice) {
+                    eprintln!("ERROR: {}", error);
+                    failed = true;
+                }
+            }
+        }
+        (None, None) => {
+            // Each sequence is read again for every pass rather than kept.
+            // Nothing at all is still worth saying so.
+            let sequences = Diagnostics::strict().finish(count_sequences(&mut input_handle));
+
+            // Bad lines are skipped, then listed once every sequence is done
+            let mut diagnostics = Diagnostics::lenient();
+            for sequence in 0..sequences.max(1) {
+                match day_01b(
+                    &mut input_handle,
+                    sequence,
+                    start_frequency,
+                    &mut diagnostics,
+                ) {
+                    Ok(repeat) => print_repeat(&repeat),
+                    Err(error) => {
+                        eprintln!("ERROR: {}", error);
+                        failed = true;
+                    }
+                }
             }
+            diagnostics.finish(Ok(()));
         }
+    }
 
-        // Re-using buffer rather than allocating new string each read.
-        input.clear();
+    if failed {
+        process::exit(1);
     }
 
-    Err(FrequencyNotRepeated {})
+    Ok(())
 }
 
-/// Enter data to test via stdin for this day's exercise.
-/// Assumes there is a solution, if not control + c
-/// :P
-fn main() -> io::Result<()> {
-    println!("Day 01: https://adventofcode.com/2018/day/1");
-
-    let stdin = io::stdin();
-
-    // Locking once for all reading.
-    let mut input_handle = stdin.lock();
-
-    // As the sequence now has to be repeated, parse it once and store
-    let mut frequency_deltas = Vec::with_capacity(1038);
-    read_frequency_deltas(&mut input_handle, &mut frequency_deltas);
+/// Every sequence, with bad lines skipped and then listed. Nothing at all
+/// is still worth saying so, as one empty sequence.
+fn read_sequences<T: BufRead>(input_handle: T) -> Vec<Sequence<i32>> {
+    let mut diagnostics = Diagnostics::lenient();
+    let result = read_frequency_sequences(input_handle, &mut diagnostics);
+    let mut sequences = diagnostics.finish(result);
 
-    // HashSet looks like the best choice. Do not need any mapping after all.
-    // The capacity I got from running the data through already.
-    // May as well give it a headstart rather it having too little then
-    // having the penality for resizing.
-    // 146478 * 4 bytes (for 32 bit ints) * 2 is still just over 1 megabyte
-    let mut seen_frequencies = HashSet::with_capacity(146478 * 2);
-
-    // This says 0 has been seen before
-    seen_frequencies.insert(0);
+    if sequences.is_empty() {
+        sequences.push(Default::default());
+    }
+    sequences
+}
 
-    // Look for that repeated frequency
-    let repeated_frequency = process_frequency_deltas(&frequency_deltas, &mut seen_frequencies);
+fn print_stepped_repeat(
+    sequence: &Sequence<i32>,
+    start_frequency: i32,
+    choice: SeenChoice,
+) -> Result<(), Error> {
+    let deltas = &sequence.deltas;
+    let mut seen_frequencies = SeenStore::new(deltas, start_frequency, choice);
+    let frequency = process_frequency_deltas(
+        deltas,
+        start_frequency,
+        &mut seen_frequencies,
+        &Budget::default(),
+    )?;
+
+    println!("{} has already been seen", frequency);
+    trace::info(format_args!(
+        "{} frequencies seen before it",
+        seen_frequencies.len()
+    ));
+    Ok(())
+}
 
+fn print_repeat(repeat: &Repeat) {
     // Not following the output standard as strictly as part 1
     // ... but as that's not really part of the test... meh
-    println!("{} has already been seen", repeated_frequency);
-    println!("Total unique frequencies found {}", seen_frequencies.len());
-
-    Ok(())
+    println!("{} has already been seen", repeat.frequency);
+    println!("First reached {}", repeat.first);
+    println!(
+        "Reached again {}, {} steps in total",
+        repeat.second, repeat.steps
+    );
 }
 
-#[cfg(test)]
-mod tests {
-    // Note this useful idiom: importing names from outer (for mod tests) scope.
-    use super::*;
-    use std::io::Seek;
-
-    #[test]
-    fn test_day_01b_example_01() {
-        // +1, -1 first reaches 0 twice.
-        let mut input = Cursor::new("1\n-1\n\n".as_bytes());
-        let mut seen_frequencies = vec![0];
-
-        let frequency = day_01b_old(&mut input, &mut seen_frequencies);
-        assert_eq!(frequency.ok(), Some(0));
+fn print_stats(
+    sequence: &Sequence<i32>,
+    start_frequency: i32,
+    stats: &StatsOptions,
+) -> Result<(), Error> {
+    let deltas = &sequence.deltas;
+
+    let repeats = repeats_within(deltas, start_frequency, stats.cycles);
+    println!(
+        "{} frequencies repeat within {} passes",
+        repeats.len(),
+        stats.cycles
+    );
+    for (frequency, occurrence) in &repeats {
+        println!("  {} again {}", frequency, occurrence);
     }
 
-    #[test]
-    fn test_day_01b_example_02() {
-        // +3, +3, +4, -2, -4 first reaches 10 twice.
-        // It requires the list to be read twice
-        let mut input = Cursor::new("3\n3\n4\n-2\n-4\n\n".as_bytes());
-        let mut seen_frequencies = vec![0];
-
-        day_01b_old(&mut input, &mut seen_frequencies).err();
-        assert_eq!(input.seek(SeekFrom::Start(0)).ok(), Some(0));
-
-        let frequency = day_01b_old(&mut input, &mut seen_frequencies);
-        assert_eq!(frequency.ok(), Some(10));
-    }
-
-    #[test]
-    fn test_day_01b_example_03() {
-        // -6, +3, +8, +5, -6 first reaches 5 twice.
-        // It requires the list to be read three times
-        let mut input = Cursor::new("-6\n3\n8\n5\n-6\n\n".as_bytes());
-        let mut seen_frequencies = vec![0];
-
-        day_01b_old(&mut input, &mut seen_frequencies).err();
-        assert_eq!(input.seek(SeekFrom::Start(0)).ok(), Some(0));
-
-        day_01b_old(&mut input, &mut seen_frequencies).err();
-        assert_eq!(input.seek(SeekFrom::Start(0)).ok(), Some(0));
-
-        let frequency = day_01b_old(&mut input, &mut seen_frequencies);
-        assert_eq!(frequency.ok(), Some(5));
+    println!("Frequencies by visits within {} passes:", stats.cycles);
+    for (visits, frequencies) in visit_histogram(deltas, start_frequency, stats.cycles) {
+        println!("  {} visits: {}", visits, frequencies);
     }
 
-    #[test]
-    fn test_day_01b_example_04() {
-        // +7, +7, -2, -7, -4 first reaches 14 twice.
-        let mut input = Cursor::new("7\n7\n-2\n-7\n-4\n\n".as_bytes());
-        let mut seen_frequencies = vec![0];
-
-        let mut frequency = day_01b_old(&mut input, &mut seen_frequencies);
-        let mut n = 0;
-
-        while frequency.is_err() {
-            assert_eq!(input.seek(SeekFrom::Start(0)).ok(), Some(0));
-            frequency = day_01b_old(&mut input, &mut seen_frequencies);
-            n = n + 1;
-        }
-
-        assert_eq!(n, 2);
-        assert_eq!(frequency.ok(), Some(14));
-    }
+    let (frequency, occurrence) =
+        nth_visit(deltas, start_frequency, stats.visits, &Budget::default())?;
+    println!(
+        "{} is the first reached {} times, {}",
+        frequency, stats.visits, occurrence
+    );
+    Ok(())
 }
@@ -1,151 +1,92 @@
-//use std::error::Error;
-//use std::collections::HashSet;
-//use std::collections::hash_map::DefaultHasher;
-//use std::collections::HashMap;
-use std::hash::{BuildHasher, BuildHasherDefault, Hasher};
-//use std::io::{self, BufRead, Read};
-use std::io::{self, Read};
-
-// #[cfg(test)]
-
-const GRAM_RANGE: usize = (1 + GRAM_RANGE_END - GRAM_RANGE_START) as usize;
-const GRAM_RANGE_START: u8 = 'a' as u8;
-const GRAM_RANGE_END: u8 = 'z' as u8;
-
-#[derive(Default)]
-struct WarehouseHasher {
-    thrice: u16,
-    twice: u16,
-    seen: [u32; GRAM_RANGE],
-}
-
-// Not sure how to do this with the BuildHasherDefault?
-// Is this the equivalent of Default anyway?
-/*
-impl Default for WarehouseHasher {
-    fn default() -> WarehouseHasher {
-        WarehouseHasher {
-            thrice: 0,
-            twice: 0,
-            new_line: false,
-            seen: [0; GRAM_RANGE],
-        }
+extern crate advent_2018;
+
+use advent_2018::cli::DayOptions;
+use advent_2018::day_02::{
+    count_multiplicities, multiplicity_options, signature, write_checksum_csv, Day02,
+    MultiplicityOptions,
+};
+use advent_2018::error::{Diagnostics, Error, Result};
+use advent_2018::report::{self, Format};
+use advent_2018::solution::Solution;
+use advent_2018::trace;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Enter data to test via stdin, or a file, for this day's exercise.
+/// `--csv` also saves the letter counts behind the checksum, and
+/// `multiplicities` looks at more than letters found twice or thrice.
+fn main() -> io::Result<()> {
+    let (options, (csv_path, multiplicities)) = DayOptions::from_env_with_commands(
+        "day_02a",
+        &["multiplicities"],
+        &["csv", "counts", "product"],
+        "[--csv file] [--counts 2,3,...] [--product 2,3,...]",
+        |args| {
+            let csv_path = args.option("csv").map(PathBuf::from);
+            if csv_path.is_some() {
+                DayOptions::require_text(args, "--csv")?;
+            }
+            Ok((csv_path, multiplicity_options(args)?))
+        },
+    );
+    let input_handle = options.open_input();
+
+    if options.format == Format::Json {
+        report::print_json("2a", input_handle);
+        return Ok(());
     }
-}
-*/
 
-impl Hasher for WarehouseHasher {
-    fn write(&mut self, bytes: &[u8]) {
-        const N: u8 = '\n' as u8;
-        const R: u8 = '\r' as u8;
+    trace::info(format_args!("Day 02: https://adventofcode.com/2018/day/2"));
 
-        // For Debug only to output csv
-        for i in GRAM_RANGE_START..GRAM_RANGE_END {
-            print!("{},", (i) as char);
+    // Bad lines are skipped, then listed once the input is done
+    let mut diagnostics = Diagnostics::lenient();
+    let result = Day02::parse(input_handle, &mut diagnostics).and_then(|box_ids| {
+        if let Some(ref path) = csv_path {
+            save_checksum_csv(&box_ids, path)?;
         }
-
-        // For Debug only to output csv
-        println!("{},twice,thrice,line", GRAM_RANGE_END as char);
-
-        let mut line_string = String::default();
-
-        for byte in bytes {
-            match byte {
-                &N | &R => {
-                    // For Debug only to output csv
-                    for count in &self.seen {
-                        print!("{},", count);
-                    }
-
-                    // Iterate over everything looking for letters occuring twice.
-                    for count in &self.seen {
-                        if *count == 2 {
-                            self.twice += 1;
-                            break;
-                        }
-                    }
-
-                    // Iterate over everything looking for letters occuring twice.
-                    for count in &self.seen {
-                        if *count == 3 {
-                            self.thrice += 1;
-                            break;
-                        }
-                    }
-
-                    // Debug only - current tally and line read in
-                    println!("{:05},{:06},{}", self.twice, self.thrice, line_string);
-
-                    // Clear line and array
-                    line_string.clear();
-
-                    for i in &mut self.seen[0..GRAM_RANGE] {
-                        *i = 0
-                    }
-                }
-
-                //TODO: Bug? Is range inclusive or exclusive?
-                GRAM_RANGE_START...GRAM_RANGE_END => {
-                    self.seen[(byte - GRAM_RANGE_START) as usize] += 1;
-                    line_string += &(*byte as char).to_string();
-                }
-
-                &_ => {}
-            }
+        match multiplicities {
+            Some(ref multiplicities) => print_multiplicities(&box_ids, multiplicities),
+            None => Day02::part1(&box_ids),
         }
-    }
-
-    fn finish(&self) -> u64 {
-        println!("twice {}   thrice {}", self.twice, self.thrice);
-        (self.twice * self.thrice) as u64
-    }
-}
-
-type WarehouseBuildHasher = BuildHasherDefault<WarehouseHasher>;
-
-/// Enter data to test via stdin for this day's exercise.
-fn main() -> io::Result<()> {
-    println!("Day 02: https://adventofcode.com/2018/day/2");
-
-    let stdin = io::stdin();
-
-    // Locking once for all reading.
-    let mut input_handle = stdin.lock();
-
-    let mut input_string = String::default();
-    match input_handle.read_to_string(&mut input_string) {
-        Ok(_) => {}     //TODO
-        Err(_err) => {} // TODO
-    }
-
-    let input_bytes = input_string.into_bytes();
-
-    let build_hasher = WarehouseBuildHasher::default();
-    let mut hasher = build_hasher.build_hasher();
-
-    hasher.write(&input_bytes);
-    println!("{}", hasher.finish());
+    });
+    println!("{}", diagnostics.finish(result));
 
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    // Note this useful idiom: importing names from outer (for mod tests) scope.
-    use super::*;
+/// Save the letter counts behind the checksum to `path`.
+fn save_checksum_csv(box_ids: &[String], path: &Path) -> Result<()> {
+    let written = File::create(path).and_then(|file| {
+        let mut out = BufWriter::new(file);
+        write_checksum_csv(box_ids, &mut out)?;
+        out.flush()
+    });
+    written.map_err(|e| Error::write(path, e))
+}
 
-    #[test]
-    fn test_day_02a_example_01() {
-        // Example taken from in exercise description
-        let input_string =
-            String::from("abcdef\nbababc\nabbcde\nabcccd\naabcdd\nabcdee\nababab\n\n");
-        //                abcdef  bababc  abbcde  abcccd  aabcdd  abcdee  ababab
-        let input_bytes = input_string.into_bytes();
-        let build_hasher = WarehouseBuildHasher::default();
-        let mut hasher = build_hasher.build_hasher();
+/// Each box ID's signature and the count for each multiplicity, giving
+/// back the product to print as the answer.
+fn print_multiplicities(box_ids: &[String], multiplicities: &MultiplicityOptions) -> Result<u64> {
+    for box_id in box_ids {
+        let signature: Vec<String> = signature(box_id).iter().map(u32::to_string).collect();
+        println!("{}: {}", box_id, signature.join(","));
+    }
 
-        hasher.write(&input_bytes);
-        let hash = hasher.finish();
-        assert_eq!(hash, 12);
+    let counted = count_multiplicities(box_ids, &multiplicities.counts);
+    for (multiplicity, count) in &counted.counts {
+        println!(
+            "{} box IDs have a letter exactly {} times",
+            count, multiplicity
+        );
     }
+
+    let chosen: Vec<String> = multiplicities.product.iter().map(u32::to_string).collect();
+    trace::info(format_args!(
+        "Product of the counts for {}",
+        chosen.join(", ")
+    ));
+    counted
+        .product(&multiplicities.product)
+        .ok_or_else(|| Error::NoSolution("the product is too big for 64 bits".to_string()))
 }
@@ -0,0 +1,351 @@
+//! A small command line parser shared by the binaries.
+//!
+//! Arguments are positionals, `--flag`s, or `--option value` (also written
+//! `--option=value`). Which options take a value is given up front so that
+//! `--iterations 5` is not read as a flag followed by a positional.
+
+use input::{self, Replay};
+use report::Format;
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use trace::{self, Level};
+
+/// Options that set up tracing, taken by every binary.
+pub const TRACE_OPTIONS: &[&str] = &["verbosity", "log", "trace"];
+
+#[derive(Debug, Default)]
+pub struct Args {
+    positionals: Vec<String>,
+    flags: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    /// Split up `args` (not including the program name). Names in
+    /// `options_with_values` take the next argument as their value.
+    pub fn parse<I>(args: I, options_with_values: &[&str]) -> Result<Args, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if !arg.starts_with("--") || arg == "--" {
+                parsed.positionals.push(arg);
+                continue;
+            }
+
+            let name = &arg[2..];
+            if let Some(equals) = name.find('=') {
+                let (name, value) = (&name[..equals], &name[equals + 1..]);
+                parsed.options.insert(name.to_string(), value.to_string());
+            } else if options_with_values.contains(&name) {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("--{} needs a value", name))?;
+                parsed.options.insert(name.to_string(), value);
+            } else {
+                parsed.flags.push(name.to_string());
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn positionals(&self) -> &[String] {
+        &self.positionals
+    }
+
+    pub fn positional(&self, index: usize) -> Option<&str> {
+        self.positionals.get(index).map(String::as_str)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    /// Parse an option's value, if it was given.
+    pub fn parsed<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        match self.option(name) {
+            Some(value) => value
+                .parse::<T>()
+                .map(Some)
+                .map_err(|_| format!("--{} has an invalid value '{}'", name, value)),
+            None => Ok(None),
+        }
+    }
+
+    /// Fail on any flag or option not in `known`, to catch typos.
+    pub fn expect_only(&self, known: &[&str]) -> Result<(), String> {
+        let unknown = self
+            .flags
+            .iter()
+            .chain(self.options.keys())
+            .find(|name| !known.contains(&name.as_str()));
+
+        match unknown {
+            Some(name) => Err(format!("Unknown option --{}", name)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Set the trace level from `--verbosity`, or `--trace` for short, and send
+/// it to `--log` if given.
+pub fn apply_trace_options(args: &Args) -> Result<(), String> {
+    if let Some(level) = args.parsed::<Level>("verbosity")? {
+        trace::set_level(level);
+    }
+    if args.flag("trace") {
+        trace::set_level(Level::Trace);
+    }
+
+    if let Some(path) = args.option("log") {
+        trace::set_output_file(Path::new(path)).map_err(|e| format!("{}: {}", path, e))?;
+    }
+
+    Ok(())
+}
+
+/// What every day binary can be asked to do.
+#[derive(Debug, PartialEq)]
+pub struct DayOptions {
+    pub format: Format,
+    /// Read from standard in if not given.
+    pub input: Option<PathBuf>,
+    /// Something other than the usual answer, given before the input.
+    pub command: Option<String>,
+}
+
+impl DayOptions {
+    /// Options taking a value.
+    pub const NAMES: &'static [&'static str] = &["format", "verbosity", "log"];
+    pub const FLAGS: &'static [&'static str] = &["trace"];
+
+    /// Read the options and set up tracing from them.
+    pub fn parse(args: &Args) -> Result<DayOptions, String> {
+        let known: Vec<&str> = DayOptions::NAMES
+            .iter()
+            .chain(DayOptions::FLAGS)
+            .cloned()
+            .collect();
+        args.expect_only(&known)?;
+        DayOptions::read(args, &[])
+    }
+
+    /// The first positional is taken as the command if it's one of
+    /// `commands`, leaving the input after it.
+    fn read(args: &Args, commands: &[&str]) -> Result<DayOptions, String> {
+        let command = args
+            .positional(0)
+            .filter(|first| commands.contains(first))
+            .map(str::to_string);
+        let skip = if command.is_some() { 1 } else { 0 };
+
+        if args.positionals().len() > skip + 1 {
+            return Err("Only one input file can be given".to_string());
+        }
+        apply_trace_options(args)?;
+
+        Ok(DayOptions {
+            format: args.parsed::<Format>("format")?.unwrap_or(Format::Text),
+            input: args.positional(skip).map(PathBuf::from),
+            command,
+        })
+    }
+
+    /// For a day's own options and commands, which have no JSON report: an
+    /// error saying so if `--format json` was given along with `what`.
+    pub fn require_text(args: &Args, what: &str) -> Result<(), String> {
+        if args.option("format") == Some("json") {
+            return Err(format!("{} only works with text output", what));
+        }
+        Ok(())
+    }
+
+    /// For the day binaries: read the options from the command line, or
+    /// exit with a usage message if they don't make sense.
+    pub fn from_env(program: &str) -> DayOptions {
+        DayOptions::from_env_with(program, &[], "", |_| Ok(())).0
+    }
+
+    /// As `from_env`, along with the day's own `options` (each taking a
+    /// value) read by `read`. `usage` describes them.
+    pub fn from_env_with<T, F>(
+        program: &str,
+        options: &[&str],
+        usage: &str,
+        read: F,
+    ) -> (DayOptions, T)
+    where
+        F: FnOnce(&Args) -> Result<T, String>,
+    {
+        DayOptions::from_env_with_commands(program, &[], options, usage, read)
+    }
+
+    /// As `from_env_with`, where the input may follow one of `commands`.
+    pub fn from_env_with_commands<T, F>(
+        program: &str,
+        commands: &[&str],
+        options: &[&str],
+        usage: &str,
+        read: F,
+    ) -> (DayOptions, T)
+    where
+        F: FnOnce(&Args) -> Result<T, String>,
+    {
+        let names: Vec<&str> = DayOptions::NAMES.iter().chain(options).cloned().collect();
+        let known: Vec<&str> = names.iter().chain(DayOptions::FLAGS).cloned().collect();
+        let parsed = Args::parse(env::args().skip(1), &names).and_then(|args| {
+            args.expect_only(&known)?;
+            Ok((DayOptions::read(&args, commands)?, read(&args)?))
+        });
+
+        parsed.unwrap_or_else(|message| {
+            eprintln!(
+                "{}\nUsage: {} {}[--format text|json] [--verbosity quiet|normal|trace] [--trace] [--log file] {}[input]",
+                message,
+                program,
+                if commands.is_empty() { String::new() } else { format!("[{}] ", commands.join("|")) },
+                if usage.is_empty() { String::new() } else { format!("{} ", usage) }
+            );
+            process::exit(1);
+        })
+    }
+
+    /// The input file, or standard in, locked once for all reading and kept
+    /// so it can be read again. Exits if the file cannot be opened.
+    pub fn open_input(&self) -> Box<dyn Replay> {
+        match self.input {
+            Some(ref path) => Box::new(self.open_file(path)),
+            None => Box::new(input::replayable(io::stdin().lock())),
+        }
+    }
+
+    /// As `open_input`, but standard in isn't kept, for input that may
+    /// never end.
+    pub fn open_stream(&self) -> Box<dyn BufRead> {
+        match self.input {
+            Some(ref path) => Box::new(self.open_file(path)),
+            None => Box::new(io::stdin().lock()),
+        }
+    }
+
+    fn open_file(&self, path: &Path) -> BufReader<File> {
+        match File::open(path) {
+            Ok(file) => BufReader::new(file),
+            Err(e) => {
+                eprintln!("ERROR: {}: {}", path.display(), e);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// For tests of a day's own options: `args` as the day binaries would read
+/// them, with `options` taking a value along with `DayOptions::NAMES`.
+#[cfg(test)]
+pub fn day_args(args: &[&str], options: &[&str]) -> Args {
+    let options: Vec<&str> = options.iter().chain(DayOptions::NAMES).cloned().collect();
+    Args::parse(args.iter().map(|arg| arg.to_string()), &options).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str], options_with_values: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()), options_with_values)
+    }
+
+    #[test]
+    fn test_positionals_flags_and_options() {
+        let args = parse(
+            &[
+                "bench",
+                "--iterations",
+                "5",
+                "1",
+                "--lenient",
+                "--save=out.json",
+            ],
+            &["iterations", "save"],
+        )
+        .unwrap();
+
+        assert_eq!(args.positionals(), &["bench", "1"]);
+        assert!(args.flag("lenient"));
+        assert!(!args.flag("iterations"));
+        assert_eq!(args.option("save"), Some("out.json"));
+        assert_eq!(args.parsed::<usize>("iterations"), Ok(Some(5)));
+        assert_eq!(args.parsed::<usize>("missing"), Ok(None));
+        assert!(args.expect_only(&["iterations", "save", "lenient"]).is_ok());
+        assert!(args.expect_only(&["iterations", "save"]).is_err());
+    }
+
+    #[test]
+    fn test_day_options() {
+        let args = parse(
+            &["--format", "json", "--verbosity=quiet"],
+            DayOptions::NAMES,
+        )
+        .unwrap();
+        assert_eq!(
+            DayOptions::parse(&args),
+            Ok(DayOptions {
+                format: Format::Json,
+                input: None,
+                command: None,
+            })
+        );
+        assert_eq!(trace::level(), Level::Quiet);
+
+        let args = parse(&["input.txt", "--trace"], DayOptions::NAMES).unwrap();
+        assert_eq!(
+            DayOptions::parse(&args).unwrap().input,
+            Some(PathBuf::from("input.txt"))
+        );
+        assert_eq!(trace::level(), Level::Trace);
+
+        let args = parse(&["one.txt", "two.txt"], DayOptions::NAMES).unwrap();
+        assert!(DayOptions::parse(&args).is_err());
+
+        let args = parse(&["stats", "input.txt"], DayOptions::NAMES).unwrap();
+        let options = DayOptions::read(&args, &["stats"]).unwrap();
+        assert_eq!(options.command, Some("stats".to_string()));
+        assert_eq!(options.input, Some(PathBuf::from("input.txt")));
+        assert!(DayOptions::parse(&args).is_err());
+
+        let args = parse(&["--verbosity", "loud"], DayOptions::NAMES).unwrap();
+        assert!(DayOptions::parse(&args).is_err());
+
+        let args = parse(&["--fast"], DayOptions::NAMES).unwrap();
+        assert!(DayOptions::parse(&args).is_err());
+
+        let args = parse(&["--format", "json"], DayOptions::NAMES).unwrap();
+        assert_eq!(
+            DayOptions::require_text(&args, "--start"),
+            Err("--start only works with text output".to_string())
+        );
+        let args = parse(&["--format=text"], DayOptions::NAMES).unwrap();
+        assert_eq!(DayOptions::require_text(&args, "--start"), Ok(()));
+    }
+
+    #[test]
+    fn test_option_errors() {
+        assert!(parse(&["--iterations"], &["iterations"]).is_err());
+
+        let args = parse(&["--iterations", "lots"], &["iterations"]).unwrap();
+        assert!(args.parsed::<usize>("iterations").is_err());
+    }
+}
@@ -0,0 +1,2141 @@
+//! Day 1: Chronal Calibration
+//!
+//! https://adventofcode.com/2018/day/1
+
+use bigint::BigInt;
+use cli::{Args, DayOptions};
+use error::{Diagnostics, Error, Result};
+use input;
+use solution::Solution;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::Hash;
+use std::io::BufRead;
+use std::mem;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use trace;
+
+/// A number type to add frequencies up in, so large inputs can pick a wider
+/// one. `BigInt` never overflows.
+pub trait Frequency: Clone + Eq + Hash + Ord + fmt::Display + fmt::Debug + FromStr {
+    /// What it is called in messages, such as `i32`.
+    const NAME: &'static str;
+
+    fn zero() -> Self;
+
+    /// `None` if the sum does not fit.
+    fn checked_add(&self, delta: &Self) -> Option<Self>;
+
+    /// From 0 up to `|modulus|`, which is not 0.
+    fn rem_euclid(&self, modulus: &Self) -> Self;
+}
+
+macro_rules! primitive_frequency {
+    ($($integer:ident),*) => {$(
+        impl Frequency for $integer {
+            const NAME: &'static str = stringify!($integer);
+
+            fn zero() -> $integer {
+                0
+            }
+
+            fn checked_add(&self, delta: &$integer) -> Option<$integer> {
+                $integer::checked_add(*self, *delta)
+            }
+
+            fn rem_euclid(&self, modulus: &$integer) -> $integer {
+                // Only MIN mod -1 fails, and anything mod 1 is 0
+                self.checked_rem_euclid(*modulus).unwrap_or(0)
+            }
+        }
+    )*};
+}
+
+primitive_frequency!(i32, i64, i128);
+
+impl Frequency for BigInt {
+    const NAME: &'static str = "big";
+
+    fn zero() -> BigInt {
+        BigInt::zero()
+    }
+
+    fn checked_add(&self, delta: &BigInt) -> Option<BigInt> {
+        Some(self + delta)
+    }
+
+    fn rem_euclid(&self, modulus: &BigInt) -> BigInt {
+        BigInt::rem_euclid(self, modulus)
+    }
+}
+
+/// `frequency + delta`, or which line it would overflow at.
+fn add_delta<F: Frequency>(frequency: &F, delta: &F, line: usize) -> Result<F> {
+    frequency.checked_add(delta).ok_or_else(|| Error::Overflow {
+        line,
+        text: format!("{:+}", delta),
+        message: format!("frequency {} {:+} overflows {}", frequency, delta, F::NAME),
+    })
+}
+
+/// `--start` for the day 1 binaries, 0 if not given. The JSON report
+/// solves the puzzle as set, so it cannot be moved.
+pub fn start_option(args: &Args) -> ::std::result::Result<i32, String> {
+    let start = args.parsed::<i32>("start")?;
+    if start.is_some() {
+        DayOptions::require_text(args, "--start")?;
+    }
+    Ok(start.unwrap_or(0))
+}
+
+/// What `day_01b stats` looks for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StatsOptions {
+    /// Find the first frequency reached this many times.
+    pub visits: u64,
+    /// List repeats and count visits over this many passes.
+    pub cycles: u64,
+}
+
+/// `--visits` (3 if not given) and `--cycles` (2) for `day_01b stats`, or
+/// `None` when not asked for stats. There's no JSON report of them.
+pub fn stats_options(args: &Args) -> ::std::result::Result<Option<StatsOptions>, String> {
+    let visits = args.parsed::<u64>("visits")?;
+    let cycles = args.parsed::<u64>("cycles")?;
+
+    if args.positional(0) != Some("stats") {
+        return match (visits, cycles) {
+            (None, None) => Ok(None),
+            _ => Err("--visits and --cycles only work with stats".to_string()),
+        };
+    }
+    DayOptions::require_text(args, "stats")?;
+
+    Ok(Some(StatsOptions {
+        visits: visits.unwrap_or(3),
+        cycles: cycles.unwrap_or(2),
+    }))
+}
+
+/// `--seen auto|bitmap|hash` for `day_01b`, or `None` if not given: step
+/// through every pass keeping the frequencies seen in that store, rather
+/// than reading the input again for each pass.
+pub fn seen_option(args: &Args) -> ::std::result::Result<Option<SeenChoice>, String> {
+    let seen = args.parsed::<SeenChoice>("seen")?;
+    if seen.is_some() {
+        if let Some(command @ "stats") | Some(command @ "stream") = args.positional(0) {
+            return Err(format!("--seen doesn't work with {}", command));
+        }
+        DayOptions::require_text(args, "--seen")?;
+    }
+    Ok(seen)
+}
+
+/// The deltas on one line, written as the puzzle does: separated by commas
+/// or whitespace, with or without a `+`, and `#` starting a comment. One bad
+/// delta makes the whole line bad, reported at the column it starts.
+fn parse_delta_line<F: Frequency>(line: &str, line_number: usize) -> Result<Vec<F>>
+where
+    F::Err: fmt::Display,
+{
+    let deltas_text = line.split('#').next().unwrap_or("");
+    let mut deltas = Vec::new();
+    let mut token_start = None;
+
+    // A separator on the end finishes off the last delta
+    let separated = deltas_text
+        .char_indices()
+        .chain(Some((deltas_text.len(), ',')));
+    for (index, character) in separated {
+        if character != ',' && !character.is_whitespace() {
+            token_start = token_start.or(Some(index));
+            continue;
+        }
+
+        if let Some(start) = token_start.take() {
+            match deltas_text[start..index].parse::<F>() {
+                Ok(delta) => deltas.push(delta),
+                Err(e) => {
+                    let column = deltas_text[..start].chars().count() + 1;
+                    return Err(Error::parse(line_number, column, line, e));
+                }
+            }
+        }
+    }
+
+    Ok(deltas)
+}
+
+/// Read the next sequence of deltas, handing each to `each` with its line
+/// number. Blank lines before it are skipped and a blank line after it ends
+/// it. Gives back whether there was a sequence at all.
+fn read_sequence<F: Frequency, T: BufRead>(
+    lines: &mut input::Lines<T>,
+    diagnostics: &mut Diagnostics,
+    mut each: impl FnMut(F, usize) -> Result<()>,
+) -> Result<bool>
+where
+    F::Err: fmt::Display,
+{
+    let mut started = false;
+
+    while let Some(line) = lines.next() {
+        let line = line?;
+        if line.trim().is_empty() {
+            if started {
+                break;
+            }
+            continue;
+        }
+
+        // Comments alone neither start nor end a sequence
+        if line.split('#').next().unwrap_or("").trim().is_empty() {
+            continue;
+        }
+        started = true;
+
+        // Skips lines that failed to parse, unless diagnostics says to stop
+        let line_number = lines.line_number();
+        if let Some(deltas) = diagnostics.check(parse_delta_line(&line, line_number))? {
+            for delta in deltas {
+                each(delta, line_number)?;
+            }
+        }
+    }
+
+    Ok(started)
+}
+
+/// Deltas for one run through the device, with the line each came from.
+#[derive(Debug, PartialEq)]
+pub struct Sequence<F> {
+    pub deltas: Vec<F>,
+    /// Counting from 1, over the whole input.
+    pub lines: Vec<usize>,
+}
+
+// Derived, it would need `F: Default` as well
+impl<F> Default for Sequence<F> {
+    fn default() -> Sequence<F> {
+        Sequence {
+            deltas: Vec::new(),
+            lines: Vec::new(),
+        }
+    }
+}
+
+/// Read every sequence in the input, as separated by blank lines, so each
+/// example pasted from the puzzle can be solved on its own.
+pub fn read_frequency_sequences<F: Frequency, T: BufRead>(
+    handle: T,
+    diagnostics: &mut Diagnostics,
+) -> Result<Vec<Sequence<F>>>
+where
+    F::Err: fmt::Display,
+{
+    let mut lines = input::all_lines(handle);
+    let mut sequences = Vec::new();
+
+    loop {
+        let mut sequence = Sequence::default();
+        let started = read_sequence(&mut lines, diagnostics, |delta, line| {
+            sequence.deltas.push(delta);
+            sequence.lines.push(line);
+            Ok(())
+        })?;
+
+        if !started {
+            return Ok(sequences);
+        }
+        sequences.push(sequence);
+    }
+}
+
+/// For `--format json` in the day 1 binaries: the whole input, unless it has
+/// more than one sequence. The JSON report solves only the first, as
+/// `aoc run` does, so it would give a different answer from text output.
+pub fn single_sequence_input<T: BufRead>(mut handle: T) -> ::std::result::Result<Vec<u8>, String> {
+    let mut input = Vec::new();
+    handle
+        .read_to_end(&mut input)
+        .map_err(|e| Error::Io(e).to_string())?;
+
+    let sequences = count_sequences(&input[..]).map_err(|e| e.to_string())?;
+    if sequences > 1 {
+        return Err(format!(
+            "--format json solves one sequence, but the input has {}",
+            sequences
+        ));
+    }
+
+    Ok(input)
+}
+
+/// How many sequences there are in `handle`, for solving each with
+/// `day_01b` without keeping them.
+pub fn count_sequences<T: BufRead>(handle: T) -> Result<usize> {
+    let mut lines = input::all_lines(handle);
+    let mut sequences = 0;
+
+    // Only counting, so bad lines and big numbers don't matter here
+    let mut diagnostics = Diagnostics::lenient();
+    while read_sequence::<BigInt, _>(&mut lines, &mut diagnostics, |_, _| Ok(()))? {
+        sequences += 1;
+    }
+
+    Ok(sequences)
+}
+
+/// `frequency + delta`, traced step by step.
+fn apply_delta<F: Frequency>(frequency: &F, delta: &F, line: usize) -> Result<F> {
+    let next_frequency = add_delta(frequency, delta, line)?;
+
+    trace::trace(format_args!(
+        "  - Current frequency {}, change of {:+}; resulting frequency {}.",
+        frequency, delta, next_frequency
+    ));
+
+    Ok(next_frequency)
+}
+
+/// Part 1 for one sequence read by `read_frequency_sequences`.
+pub fn sum_frequency_deltas<F: Frequency>(sequence: &Sequence<F>, start_frequency: F) -> Result<F> {
+    let mut current_frequency = start_frequency;
+    for (delta, line) in sequence.deltas.iter().zip(&sequence.lines) {
+        current_frequency = apply_delta(&current_frequency, delta, *line)?;
+    }
+
+    Ok(current_frequency)
+}
+
+/// Solution to day 1 part 1
+/// Reads the first sequence, see `read_frequency_sequences`. Lines that are
+/// not integers go to `diagnostics`.
+pub fn day_01a<F: Frequency, T: BufRead>(
+    handle: T,
+    start_frequency: F,
+    diagnostics: &mut Diagnostics,
+) -> Result<F>
+where
+    F::Err: fmt::Display,
+{
+    // Allow user to stipulate start frequency rather than assuming zero.AsMut
+    let mut current_frequency = start_frequency;
+
+    read_sequence(&mut input::all_lines(handle), diagnostics, |delta, line| {
+        current_frequency = apply_delta(&current_frequency, &delta, line)?;
+        Ok(())
+    })?;
+
+    Ok(current_frequency)
+}
+
+/// What happened on reading a delta from a `FrequencyStream`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FrequencyEvent<F> {
+    /// `delta`, from `line`, moved the running total on to `frequency`.
+    Changed { delta: F, frequency: F, line: usize },
+    /// `frequency` had been reached before, for the first time in the
+    /// stream. Follows the `Changed` that reached it again.
+    FirstRepeat {
+        frequency: F,
+        line: usize,
+        step: u64,
+    },
+}
+
+/// Running totals over input that may never end, given as each delta is
+/// read rather than once the input is done.
+///
+/// The input is read a buffer at a time and split on commas and whitespace
+/// as it comes, so only the total and the delta being read are kept, even
+/// for one endless line. Watching for repeats keeps every frequency as well,
+/// until the first one comes round again. Blank lines are skipped, so the
+/// whole stream is the one sequence. A bad delta comes out as an error and
+/// the stream carries on, but it stops at a total too big for `F`.
+pub struct FrequencyStream<F, T> {
+    handle: T,
+    current_frequency: F,
+    scanner: DeltaScanner,
+    steps: u64,
+    /// Frequencies reached so far, while watching for a repeat.
+    seen_frequencies: Option<HashSet<F>>,
+    repeat: Option<FrequencyEvent<F>>,
+    finished: bool,
+}
+
+/// Stream the deltas in `handle` from `start_frequency`.
+pub fn frequency_stream<F: Frequency, T: BufRead>(
+    handle: T,
+    start_frequency: F,
+) -> FrequencyStream<F, T> {
+    FrequencyStream {
+        handle,
+        current_frequency: start_frequency,
+        scanner: DeltaScanner {
+            carry: Vec::new(),
+            token_line: 1,
+            token_column: 1,
+            line: 1,
+            column: 0,
+            in_comment: false,
+        },
+        steps: 0,
+        seen_frequencies: None,
+        repeat: None,
+        finished: false,
+    }
+}
+
+/// Splits the input into deltas a buffer at a time, for `FrequencyStream`.
+struct DeltaScanner {
+    /// The start of a delta that ran off the end of the last buffer.
+    carry: Vec<u8>,
+    /// Where the delta being read started, counting lines and columns from 1.
+    token_line: usize,
+    token_column: usize,
+    /// Where reading is up to. `column` counts the characters read so far
+    /// on this line.
+    line: usize,
+    column: usize,
+    in_comment: bool,
+}
+
+impl DeltaScanner {
+    /// Read `bytes` up to the end of the next delta, giving back how many
+    /// were used and, if one was finished, the delta and its line. Only a
+    /// delta cut off by the end of `bytes` is copied, to finish next time.
+    fn scan<F: Frequency>(&mut self, bytes: &[u8]) -> (usize, Option<Result<(F, usize)>>)
+    where
+        F::Err: fmt::Display,
+    {
+        // Where the delta's bytes in this buffer start
+        let mut start = None;
+
+        for (index, byte) in bytes.iter().enumerate() {
+            let ends_token = match *byte {
+                b'\n' => {
+                    self.line += 1;
+                    self.column = 0;
+                    self.in_comment = false;
+                    true
+                }
+                _ if self.in_comment => false,
+                b'#' => {
+                    self.in_comment = true;
+                    true
+                }
+                b',' => true,
+                _ if byte.is_ascii_whitespace() => true,
+                _ => {
+                    if start.is_none() {
+                        if self.carry.is_empty() {
+                            self.token_line = self.line;
+                            self.token_column = self.column + 1;
+                        }
+                        start = Some(index);
+                    }
+                    false
+                }
+            };
+
+            // Continuation bytes are part of the character before
+            if *byte != b'\n' && *byte & 0xc0 != 0x80 {
+                self.column += 1;
+            }
+            if !ends_token {
+                continue;
+            }
+
+            if self.carry.is_empty() {
+                if let Some(start) = start {
+                    return (index + 1, Some(self.parse(&bytes[start..index])));
+                }
+            } else {
+                self.carry
+                    .extend_from_slice(&bytes[start.unwrap_or(index)..index]);
+                return (index + 1, self.finish());
+            }
+        }
+
+        if let Some(start) = start {
+            self.carry.extend_from_slice(&bytes[start..]);
+        }
+        (bytes.len(), None)
+    }
+
+    /// The delta cut off by the end of the input, if there is one.
+    fn finish<F: Frequency>(&mut self) -> Option<Result<(F, usize)>>
+    where
+        F::Err: fmt::Display,
+    {
+        if self.carry.is_empty() {
+            return None;
+        }
+
+        let carry = mem::take(&mut self.carry);
+        let parsed = self.parse(&carry);
+        // Keep the room made for the next delta
+        self.carry = carry;
+        self.carry.clear();
+        Some(parsed)
+    }
+
+    fn parse<F: Frequency>(&self, token: &[u8]) -> Result<(F, usize)>
+    where
+        F::Err: fmt::Display,
+    {
+        let token = String::from_utf8_lossy(token);
+
+        // Only the start of the input can have a byte order mark
+        let at_start = self.token_line == 1 && self.token_column == 1;
+        let text = match token.strip_prefix('\u{feff}') {
+            Some(rest) if at_start => rest,
+            _ => &token,
+        };
+        let delta = text
+            .parse::<F>()
+            .map_err(|e| Error::parse(self.token_line, self.token_column, &token, e))?;
+        Ok((delta, self.token_line))
+    }
+}
+
+impl<F: Frequency, T> FrequencyStream<F, T> {
+    /// Also give the first repeat, counting the start frequency as seen.
+    pub fn watch_repeats(mut self) -> FrequencyStream<F, T> {
+        let mut seen_frequencies = HashSet::new();
+        seen_frequencies.insert(self.current_frequency.clone());
+        self.seen_frequencies = Some(seen_frequencies);
+        self
+    }
+
+    /// The running total so far.
+    pub fn frequency(&self) -> &F {
+        &self.current_frequency
+    }
+
+    fn apply(&mut self, delta: F, line: usize) -> Result<FrequencyEvent<F>> {
+        let frequency = match apply_delta(&self.current_frequency, &delta, line) {
+            Ok(frequency) => frequency,
+            Err(e) => {
+                self.finished = true;
+                return Err(e);
+            }
+        };
+        self.steps += 1;
+        self.current_frequency = frequency.clone();
+
+        // Once it's found there's nothing more to look for
+        let repeated = match self.seen_frequencies {
+            Some(ref mut seen_frequencies) => !seen_frequencies.insert(frequency.clone()),
+            None => false,
+        };
+        if repeated {
+            self.seen_frequencies = None;
+            self.repeat = Some(FrequencyEvent::FirstRepeat {
+                frequency: frequency.clone(),
+                line,
+                step: self.steps,
+            });
+        }
+
+        Ok(FrequencyEvent::Changed {
+            delta,
+            frequency,
+            line,
+        })
+    }
+}
+
+impl<F: Frequency, T: BufRead> Iterator for FrequencyStream<F, T>
+where
+    F::Err: fmt::Display,
+{
+    type Item = Result<FrequencyEvent<F>>;
+
+    fn next(&mut self) -> Option<Result<FrequencyEvent<F>>> {
+        if let Some(repeat) = self.repeat.take() {
+            return Some(Ok(repeat));
+        }
+
+        while !self.finished {
+            let (used, scanned) = match self.handle.fill_buf() {
+                // The end of the input finishes off the last delta
+                Ok([]) => {
+                    self.finished = true;
+                    (0, self.scanner.finish())
+                }
+                Ok(bytes) => self.scanner.scan(bytes),
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(Error::Io(e)));
+                }
+            };
+            self.handle.consume(used);
+
+            if let Some(scanned) = scanned {
+                return Some(scanned.and_then(|(delta, line)| self.apply(delta, line)));
+            }
+        }
+
+        None
+    }
+}
+
+/// For the `stream` command of the day 1 binaries: print each event as it
+/// happens, and errors as they're found. Whether every line was good.
+pub fn print_frequency_stream<F: Frequency, T: BufRead>(stream: FrequencyStream<F, T>) -> bool
+where
+    F::Err: fmt::Display,
+{
+    let mut all_good = true;
+
+    for event in stream {
+        match event {
+            Ok(FrequencyEvent::Changed { frequency, .. }) => {
+                println!("Resulting frequency {}", frequency)
+            }
+            Ok(FrequencyEvent::FirstRepeat {
+                frequency,
+                line,
+                step,
+            }) => {
+                println!("{} has already been seen", frequency);
+                trace::info(format_args!(
+                    "Reached again on line {}, {} steps in",
+                    line, step
+                ));
+            }
+            Err(error) => {
+                eprintln!("ERROR: {}", error);
+                all_good = false;
+            }
+        }
+    }
+
+    all_good
+}
+
+/// `stream` has no JSON report, for either part.
+pub fn check_stream_format(args: &Args) -> ::std::result::Result<(), String> {
+    if args.positional(0) == Some("stream") {
+        DayOptions::require_text(args, "stream")?;
+    }
+    Ok(())
+}
+
+/// Not found error state for when frequency is not repeated, with why
+#[derive(Debug, PartialEq)]
+pub enum FrequencyNotRepeated<F> {
+    /// There were no deltas to loop over.
+    NoDeltas,
+    /// Each pass moves every frequency on by `drift` and none of them ever
+    /// lands on another, as no two share a remainder mod `drift`.
+    DriftsApart { drift: F },
+    /// The budget ran out first. There may still be a repeat further on.
+    OutOfBudget { steps: u64, elapsed: Duration },
+    /// The frequency no longer fits in its number type at this point.
+    Overflow(Occurrence),
+    /// No frequency is ever reached `visits` times, only `most` at most.
+    TooFewVisits { visits: u64, most: u64 },
+}
+
+impl<F: fmt::Display> fmt::Display for FrequencyNotRepeated<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Frequency not repeated in sequence: ")?;
+        match self {
+            FrequencyNotRepeated::NoDeltas => write!(f, "there are no frequency changes"),
+            FrequencyNotRepeated::DriftsApart { drift } => write!(
+                f,
+                "frequencies drift by {:+} each pass and never meet",
+                drift
+            ),
+            FrequencyNotRepeated::OutOfBudget { steps, elapsed } => {
+                write!(f, "gave up after {} steps in {:.1?}", steps, elapsed)
+            }
+            FrequencyNotRepeated::Overflow(occurrence) => {
+                write!(f, "frequency overflows {}", occurrence)
+            }
+            FrequencyNotRepeated::TooFewVisits { visits, most } => write!(
+                f,
+                "no frequency is reached {} times, {} at most",
+                visits, most
+            ),
+        }
+    }
+}
+
+impl<F: fmt::Display> From<FrequencyNotRepeated<F>> for Error {
+    fn from(e: FrequencyNotRepeated<F>) -> Error {
+        Error::NoSolution(e.to_string())
+    }
+}
+
+/// Read the frequency changes in `handle` into `frequency_deltas`.
+/// Only the first sequence, see `read_frequency_sequences`. Lines that are
+/// not integers go to `diagnostics`, and the running total over the first
+/// pass is checked to fit in `F`.
+pub fn read_frequency_deltas<F: Frequency, T: BufRead>(
+    handle: &mut T,
+    frequency_deltas: &mut Vec<F>,
+    diagnostics: &mut Diagnostics,
+) -> Result<()>
+where
+    F::Err: fmt::Display,
+{
+    let mut current_frequency = F::zero();
+
+    read_sequence(&mut input::all_lines(handle), diagnostics, |delta, line| {
+        current_frequency = add_delta(&current_frequency, &delta, line)?;
+        frequency_deltas.push(delta);
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+/// How long `process_frequency_deltas` may loop for. Unlimited by default.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Budget {
+    /// Most deltas to apply.
+    pub steps: Option<u64>,
+    /// Most time to spend, checked at the end of each pass.
+    pub time: Option<Duration>,
+}
+
+/// Whether looping over `frequency_deltas` ever repeats a frequency, from
+/// any start.
+/// Nothing repeats with no deltas, or when every first pass frequency has a
+/// remainder mod the drift (the sum of the deltas) of its own, as each pass
+/// only moves them on by the drift. A first pass too big for `F` is an
+/// overflow at that delta (counting from 1), as nothing can be shown then.
+pub fn check_repeats<F: Frequency>(frequency_deltas: &[F]) -> Result<()> {
+    if frequency_deltas.is_empty() {
+        return Err(FrequencyNotRepeated::<F>::NoDeltas.into());
+    }
+
+    let mut drift = F::zero();
+    for (index, delta) in frequency_deltas.iter().enumerate() {
+        drift = add_delta(&drift, delta, index + 1)?;
+    }
+    if drift == F::zero() {
+        // Back to 0 at the end of the first pass
+        return Ok(());
+    }
+
+    let mut remainders = HashSet::with_capacity(frequency_deltas.len());
+    let mut current_frequency = F::zero();
+    for (index, delta) in frequency_deltas.iter().enumerate() {
+        if !remainders.insert(current_frequency.rem_euclid(&drift)) {
+            return Ok(());
+        }
+        current_frequency = add_delta(&current_frequency, delta, index + 1)?;
+    }
+
+    Err(FrequencyNotRepeated::DriftsApart { drift }.into())
+}
+
+/// Somewhere to keep the frequencies reached, for `process_frequency_deltas`.
+pub trait SeenFrequencies<F> {
+    /// Note `frequency` as reached, giving back whether it was new.
+    fn insert(&mut self, frequency: F) -> bool;
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<F: Eq + Hash> SeenFrequencies<F> for HashSet<F> {
+    fn insert(&mut self, frequency: F) -> bool {
+        HashSet::insert(self, frequency)
+    }
+
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+}
+
+/// Lowest and highest frequencies `process_frequency_deltas` can reach from
+/// `start_frequency` before the first repeat.
+///
+/// A repeat `m` passes on needs two first pass frequencies `m * drift` apart,
+/// so `m` is at most the spread of the first pass over the drift, and by then
+/// the first pass has moved on by at most that spread again.
+pub fn reachable_range(frequency_deltas: &[i32], start_frequency: i32) -> (i64, i64) {
+    let mut current_frequency = i64::from(start_frequency);
+    let (mut lowest, mut highest) = (current_frequency, current_frequency);
+    for delta in frequency_deltas {
+        current_frequency += i64::from(*delta);
+        lowest = lowest.min(current_frequency);
+        highest = highest.max(current_frequency);
+    }
+
+    let drift = current_frequency - i64::from(start_frequency);
+    if drift == 0 {
+        return (lowest, highest);
+    }
+    let moved = (highest - lowest) / drift.abs() * drift;
+    (lowest + moved.min(0), highest + moved.max(0))
+}
+
+/// Seen frequencies as one bit each over a range, which takes far less
+/// memory than hashing when most of the range gets reached. Any outside the
+/// range are hashed instead.
+pub struct FrequencyBitmap {
+    lowest: i64,
+    bits: Vec<u64>,
+    outside: HashSet<i32>,
+    len: usize,
+}
+
+impl FrequencyBitmap {
+    /// Room for every frequency from `lowest` to `highest`, both included.
+    pub fn new(lowest: i64, highest: i64) -> FrequencyBitmap {
+        let width = (highest - lowest).max(-1) + 1;
+        FrequencyBitmap {
+            lowest,
+            bits: vec![0; (width as usize).div_ceil(64)],
+            outside: HashSet::new(),
+            len: 0,
+        }
+    }
+}
+
+impl SeenFrequencies<i32> for FrequencyBitmap {
+    fn insert(&mut self, frequency: i32) -> bool {
+        let offset = i64::from(frequency) - self.lowest;
+        let (word, bit) = ((offset / 64) as usize, offset % 64);
+
+        let new = if offset < 0 || word >= self.bits.len() {
+            self.outside.insert(frequency)
+        } else {
+            let new = self.bits[word] & (1 << bit) == 0;
+            self.bits[word] |= 1 << bit;
+            new
+        };
+
+        if new {
+            self.len += 1;
+        }
+        new
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// Which store `SeenStore::new` picks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SeenChoice {
+    /// A bitmap if the reachable range takes no more than
+    /// `SeenStore::BITMAP_MOST_BITS`, otherwise a hash set.
+    Auto,
+    Bitmap,
+    Hash,
+}
+
+impl FromStr for SeenChoice {
+    type Err = String;
+
+    fn from_str(choice: &str) -> ::std::result::Result<SeenChoice, String> {
+        match choice {
+            "auto" => Ok(SeenChoice::Auto),
+            "bitmap" => Ok(SeenChoice::Bitmap),
+            "hash" => Ok(SeenChoice::Hash),
+            _ => Err(format!(
+                "Unknown store '{}', try auto, bitmap or hash",
+                choice
+            )),
+        }
+    }
+}
+
+/// Seen frequencies for looping over `i32` deltas, in whichever store suits.
+pub enum SeenStore {
+    Bitmap(FrequencyBitmap),
+    Hash(HashSet<i32>),
+}
+
+impl SeenStore {
+    /// 16 MiB of bitmap.
+    pub const BITMAP_MOST_BITS: i64 = 1 << 27;
+
+    /// A store for looping over `frequency_deltas` from `start_frequency`.
+    pub fn new(frequency_deltas: &[i32], start_frequency: i32, choice: SeenChoice) -> SeenStore {
+        let (lowest, highest) = reachable_range(frequency_deltas, start_frequency);
+        match choice {
+            SeenChoice::Auto if highest - lowest >= SeenStore::BITMAP_MOST_BITS => {
+                SeenStore::Hash(HashSet::new())
+            }
+            SeenChoice::Auto | SeenChoice::Bitmap => {
+                SeenStore::Bitmap(FrequencyBitmap::new(lowest, highest))
+            }
+            SeenChoice::Hash => SeenStore::Hash(HashSet::new()),
+        }
+    }
+}
+
+impl SeenFrequencies<i32> for SeenStore {
+    fn insert(&mut self, frequency: i32) -> bool {
+        match self {
+            SeenStore::Bitmap(bitmap) => bitmap.insert(frequency),
+            SeenStore::Hash(set) => SeenFrequencies::insert(set, frequency),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            SeenStore::Bitmap(bitmap) => bitmap.len(),
+            SeenStore::Hash(set) => set.len(),
+        }
+    }
+}
+
+/// Keep applying deltas until a repeat frequency is found
+/// The start frequency counts as seen. Inputs that would never repeat are
+/// turned away before looping, given nothing else has been seen, and
+/// `budget` stops it early.
+pub fn process_frequency_deltas<F: Frequency, S: SeenFrequencies<F>>(
+    frequency_deltas: &[F],
+    start_frequency: F,
+    seen_frequencies: &mut S,
+    budget: &Budget,
+) -> Result<F> {
+    check_repeats(frequency_deltas)?;
+
+    let length = frequency_deltas.len() as u64;
+    let mut current_frequency = start_frequency;
+    seen_frequencies.insert(current_frequency.clone());
+    let mut steps: u64 = 0;
+    let started = Instant::now();
+
+    loop {
+        for delta in frequency_deltas {
+            if budget.steps.is_some_and(|most| steps >= most) {
+                return Err(FrequencyNotRepeated::<F>::OutOfBudget {
+                    steps,
+                    elapsed: started.elapsed(),
+                }
+                .into());
+            }
+            steps += 1;
+
+            current_frequency = match current_frequency.checked_add(delta) {
+                Some(frequency) => frequency,
+                None => {
+                    return Err(
+                        FrequencyNotRepeated::<F>::Overflow(Occurrence::at(steps, length)).into(),
+                    )
+                }
+            };
+            if !seen_frequencies.insert(current_frequency.clone()) {
+                return Ok(current_frequency);
+            }
+        }
+
+        if budget.time.is_some_and(|most| started.elapsed() >= most) {
+            return Err(FrequencyNotRepeated::<F>::OutOfBudget {
+                steps,
+                elapsed: started.elapsed(),
+            }
+            .into());
+        }
+    }
+}
+
+/// When a frequency was reached, as the number of deltas applied so far.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Occurrence {
+    pub step: u64,
+    /// Passes through the list are counted from 0.
+    pub pass: u64,
+    /// The delta that produced it, or `None` for the starting frequency.
+    pub delta_index: Option<usize>,
+}
+
+impl Occurrence {
+    /// Where `step` falls in passes through `length` deltas.
+    pub fn at(step: u64, length: u64) -> Occurrence {
+        match step {
+            0 => Occurrence {
+                step,
+                pass: 0,
+                delta_index: None,
+            },
+            _ => Occurrence {
+                step,
+                pass: (step - 1) / length,
+                delta_index: Some(((step - 1) % length) as usize),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Occurrence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.delta_index {
+            None => write!(f, "at the start"),
+            Some(index) => write!(
+                f,
+                "after step {} (pass {}, delta {})",
+                self.step, self.pass, index
+            ),
+        }
+    }
+}
+
+/// The first frequency reached twice, and where.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Repeat {
+    pub frequency: i32,
+    pub first: Occurrence,
+    pub second: Occurrence,
+    /// Deltas applied to get to the repeat.
+    pub steps: u64,
+}
+
+/// Find the first frequency reached twice without stepping through every
+/// pass. O(n log n) however many passes the repeat takes.
+///
+/// After the first pass every frequency is a first pass frequency `s`
+/// plus some number of `drift`s, the sum of all the deltas. So `s` comes
+/// round again `m` passes later only if some other first pass frequency is
+/// `s + m * drift`, which needs the two to share a remainder mod `drift`.
+/// Sorting by remainder then frequency puts the nearest such one next to
+/// `s`, and the repeat that happens soonest is the answer.
+///
+/// Worked out in `i64`, so only the frequency repeated has to fit an `i32`.
+pub fn find_repeat(
+    frequency_deltas: &[i32],
+    start_frequency: i32,
+) -> ::std::result::Result<Repeat, FrequencyNotRepeated<i64>> {
+    let length = frequency_deltas.len() as u64;
+    let repeat = |frequency: i64, first: u64, second: u64| match i32::try_from(frequency) {
+        Ok(frequency) => Ok(Repeat {
+            frequency,
+            first: Occurrence::at(first, length),
+            second: Occurrence::at(second, length),
+            steps: second,
+        }),
+        Err(_) => Err(FrequencyNotRepeated::Overflow(Occurrence::at(
+            second, length,
+        ))),
+    };
+
+    // Frequencies at the start of each step of the first pass
+    let mut frequencies = Vec::with_capacity(frequency_deltas.len());
+    let mut current_frequency = i64::from(start_frequency);
+    let mut seen_frequencies = HashMap::with_capacity(frequency_deltas.len());
+
+    for (step, delta) in frequency_deltas.iter().enumerate() {
+        // Repeats within the first pass come first
+        if let Some(first) = seen_frequencies.insert(current_frequency, step) {
+            return repeat(current_frequency, first as u64, step as u64);
+        }
+        frequencies.push(current_frequency);
+        current_frequency += i64::from(*delta);
+    }
+
+    // Looping over nothing would never find a repeat
+    if frequency_deltas.is_empty() {
+        return Err(FrequencyNotRepeated::NoDeltas);
+    }
+
+    let drift = current_frequency - i64::from(start_frequency);
+    if drift == 0 {
+        // Back to the start at the end of the first pass
+        return repeat(i64::from(start_frequency), 0, length);
+    }
+
+    // (remainder, frequency, step in the pass)
+    let mut by_remainder: Vec<(i64, i64, usize)> = frequencies
+        .iter()
+        .enumerate()
+        .map(|(step, frequency)| (i64::rem_euclid(*frequency, drift.abs()), *frequency, step))
+        .collect();
+    by_remainder.sort();
+
+    // (time the repeat happens, frequency repeated, when it was first seen)
+    let mut first_repeat: Option<(u64, i64, usize)> = None;
+
+    for pair in by_remainder.windows(2) {
+        let ((remainder, lower, lower_step), (next_remainder, upper, upper_step)) =
+            (pair[0], pair[1]);
+        if remainder != next_remainder {
+            continue;
+        }
+
+        // Drifting up, the lower frequency reaches the upper one, and down
+        // the other way round
+        let passes = ((upper - lower) / drift.abs()) as u64;
+        let (step, repeated, first) = if drift > 0 {
+            (lower_step, upper, upper_step)
+        } else {
+            (upper_step, lower, lower_step)
+        };
+
+        let time = passes * length + step as u64;
+        if first_repeat.is_none_or(|(first_time, _, _)| time < first_time) {
+            first_repeat = Some((time, repeated, first));
+        }
+    }
+
+    match first_repeat {
+        Some((time, frequency, first)) => repeat(frequency, first as u64, time),
+        None => Err(FrequencyNotRepeated::DriftsApart { drift }),
+    }
+}
+
+/// Just the frequency from `find_repeat`, starting from 0.
+pub fn find_repeated_frequency(
+    frequency_deltas: &[i32],
+) -> ::std::result::Result<i32, FrequencyNotRepeated<i64>> {
+    find_repeat(frequency_deltas, 0).map(|repeat| repeat.frequency)
+}
+
+/// Step through the deltas from `start_frequency`, counting visits to each
+/// frequency in `visits`, for `cycles` passes or until `visit` gives back
+/// false. `visit` sees each frequency reached, with how many times it has
+/// been reached now and when, starting from the start frequency itself.
+fn walk_frequencies<V>(
+    frequency_deltas: &[i32],
+    start_frequency: i32,
+    cycles: Option<u64>,
+    visits: &mut HashMap<i64, u64>,
+    mut visit: V,
+) where
+    V: FnMut(i64, u64, Occurrence) -> bool,
+{
+    let length = frequency_deltas.len() as u64;
+    let mut current_frequency = i64::from(start_frequency);
+    let mut steps: u64 = 0;
+
+    let count = visits.entry(current_frequency).or_insert(0);
+    *count += 1;
+    if !visit(current_frequency, *count, Occurrence::at(steps, length)) {
+        return;
+    }
+
+    let mut pass = 0;
+    while length > 0 && cycles.is_none_or(|cycles| pass < cycles) {
+        for delta in frequency_deltas {
+            steps += 1;
+            current_frequency += i64::from(*delta);
+
+            let count = visits.entry(current_frequency).or_insert(0);
+            *count += 1;
+            if !visit(current_frequency, *count, Occurrence::at(steps, length)) {
+                return;
+            }
+        }
+        pass += 1;
+    }
+}
+
+/// The most times looping over `frequency_deltas` can reach any one
+/// frequency, or `None` if there's no limit.
+/// With no drift every pass goes round the same frequencies again. Otherwise
+/// each first pass frequency drifts through every later one with the same
+/// remainder mod the drift, so the last of those is reached once for each.
+pub fn most_visits(frequency_deltas: &[i32]) -> Option<u64> {
+    let drift: i64 = frequency_deltas.iter().map(|delta| i64::from(*delta)).sum();
+    if frequency_deltas.is_empty() {
+        return Some(1);
+    }
+    if drift == 0 {
+        return None;
+    }
+
+    let mut by_remainder: HashMap<i64, u64> = HashMap::new();
+    let mut current_frequency: i64 = 0;
+    for delta in frequency_deltas {
+        *by_remainder
+            .entry(i64::rem_euclid(current_frequency, drift.abs()))
+            .or_insert(0) += 1;
+        current_frequency += i64::from(*delta);
+    }
+
+    by_remainder.values().max().cloned()
+}
+
+/// The first frequency reached `visits` times, and when. The start frequency
+/// counts as its first visit, so 2 visits is the same as `find_repeat`.
+/// Inputs where no frequency gets that many are turned away before looping,
+/// and `budget` stops it early.
+pub fn nth_visit(
+    frequency_deltas: &[i32],
+    start_frequency: i32,
+    visits: u64,
+    budget: &Budget,
+) -> ::std::result::Result<(i64, Occurrence), FrequencyNotRepeated<i64>> {
+    if visits > 1 && frequency_deltas.is_empty() {
+        return Err(FrequencyNotRepeated::NoDeltas);
+    }
+    if let Some(most) = most_visits(frequency_deltas).filter(|most| visits > *most) {
+        return Err(FrequencyNotRepeated::TooFewVisits { visits, most });
+    }
+
+    let length = frequency_deltas.len() as u64;
+    let started = Instant::now();
+    let mut found = Err(FrequencyNotRepeated::NoDeltas);
+
+    walk_frequencies(
+        frequency_deltas,
+        start_frequency,
+        None,
+        &mut HashMap::new(),
+        |frequency, count, occurrence| {
+            if count >= visits {
+                found = Ok((frequency, occurrence));
+                return false;
+            }
+
+            let out_of_steps = budget.steps.is_some_and(|most| occurrence.step >= most);
+            let end_of_pass = occurrence.step > 0 && occurrence.step % length == 0;
+            let out_of_time =
+                end_of_pass && budget.time.is_some_and(|most| started.elapsed() >= most);
+            if out_of_steps || out_of_time {
+                found = Err(FrequencyNotRepeated::OutOfBudget {
+                    steps: occurrence.step,
+                    elapsed: started.elapsed(),
+                });
+                return false;
+            }
+            true
+        },
+    );
+
+    found
+}
+
+/// Every frequency reached more than once within the first `cycles` passes,
+/// with when it was first reached again, in that order.
+pub fn repeats_within(
+    frequency_deltas: &[i32],
+    start_frequency: i32,
+    cycles: u64,
+) -> Vec<(i64, Occurrence)> {
+    let mut repeats = Vec::new();
+
+    walk_frequencies(
+        frequency_deltas,
+        start_frequency,
+        Some(cycles),
+        &mut HashMap::new(),
+        |frequency, count, occurrence| {
+            if count == 2 {
+                repeats.push((frequency, occurrence));
+            }
+            true
+        },
+    );
+
+    repeats
+}
+
+/// How many frequencies were reached once, twice, and so on within the first
+/// `cycles` passes, keyed by the number of visits.
+pub fn visit_histogram(
+    frequency_deltas: &[i32],
+    start_frequency: i32,
+    cycles: u64,
+) -> BTreeMap<u64, usize> {
+    let mut visits = HashMap::new();
+    walk_frequencies(
+        frequency_deltas,
+        start_frequency,
+        Some(cycles),
+        &mut visits,
+        |_, _, _| true,
+    );
+
+    let mut histogram = BTreeMap::new();
+    for count in visits.values() {
+        *histogram.entry(*count).or_insert(0) += 1;
+    }
+    histogram
+}
+
+/// Part 2 reading the input again for each pass rather than keeping the
+/// deltas, so only the frequencies reached are held. Gives the same as
+/// `find_repeat` over sequence number `sequence`, counting from 0.
+///
+/// Once the first pass is read it's known whether there will ever be a
+/// repeat, as for `check_repeats`. Bad lines are only reported on that pass,
+/// and only for the sequence being solved.
+pub fn day_01b<T: input::Replay + ?Sized>(
+    handle: &mut T,
+    sequence: usize,
+    start_frequency: i32,
+    diagnostics: &mut Diagnostics,
+) -> Result<Repeat> {
+    // When each frequency was first reached
+    let mut seen_frequencies = HashMap::new();
+    seen_frequencies.insert(start_frequency, 0);
+    let mut current_frequency = start_frequency;
+    let mut steps: u64 = 0;
+    // (first reached, reached again)
+    let mut repeat: Option<(u64, u64)> = None;
+    let mut first_pass = true;
+
+    loop {
+        handle.replay()?;
+        let mut lines = input::all_lines(&mut *handle);
+
+        // Earlier sequences are read past, their bad lines left for them
+        for _ in 0..sequence {
+            read_sequence::<i32, _>(&mut lines, &mut Diagnostics::lenient(), |_, _| Ok(()))?;
+        }
+
+        let mut later_diagnostics = Diagnostics::lenient();
+        let pass_diagnostics = if first_pass {
+            &mut *diagnostics
+        } else {
+            &mut later_diagnostics
+        };
+
+        // The rest of the pass is still read to know its length
+        let mut length: u64 = 0;
+        read_sequence(&mut lines, pass_diagnostics, |delta: i32, line| {
+            length += 1;
+            if repeat.is_some() {
+                return Ok(());
+            }
+
+            steps += 1;
+            current_frequency = apply_delta(&current_frequency, &delta, line)?;
+            if let Some(first) = seen_frequencies.insert(current_frequency, steps) {
+                repeat = Some((first, steps));
+            }
+            Ok(())
+        })?;
+
+        if let Some((first, second)) = repeat {
+            return Ok(Repeat {
+                frequency: current_frequency,
+                first: Occurrence::at(first, length),
+                second: Occurrence::at(second, length),
+                steps: second,
+            });
+        }
+
+        if first_pass {
+            if length == 0 {
+                return Err(FrequencyNotRepeated::<i32>::NoDeltas.into());
+            }
+
+            // Back at the start would have been a repeat, so there's drift.
+            // Where the pass ends is just the start moved on by it.
+            let drift = i64::from(current_frequency) - i64::from(start_frequency);
+            let mut remainders = HashSet::with_capacity(seen_frequencies.len());
+            let shared = seen_frequencies
+                .iter()
+                .filter(|&(_, step)| *step < length)
+                .any(|(frequency, _)| {
+                    !remainders.insert(i64::rem_euclid(i64::from(*frequency), drift.abs()))
+                });
+            if !shared {
+                return Err(FrequencyNotRepeated::DriftsApart { drift }.into());
+            }
+            first_pass = false;
+        }
+    }
+}
+
+/// Part 1 sums the deltas, part 2 finds the first frequency reached twice.
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<i32>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse<T: BufRead>(mut handle: T, diagnostics: &mut Diagnostics) -> Result<Vec<i32>> {
+        let mut frequency_deltas = Vec::new();
+        read_frequency_deltas(&mut handle, &mut frequency_deltas, diagnostics)?;
+        Ok(frequency_deltas)
+    }
+
+    fn part1(frequency_deltas: &Vec<i32>) -> Result<i32> {
+        // Parsing has already checked the running total fits
+        Ok(frequency_deltas.iter().sum())
+    }
+
+    fn part2(frequency_deltas: &Vec<i32>) -> Result<i32> {
+        Ok(find_repeated_frequency(frequency_deltas)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use cli::day_args;
+    use error::Mode;
+    use std::fs::File;
+    use std::io::{self, BufReader, Cursor};
+
+    #[test]
+    fn test_day_01_example_01() {
+        // +1, +1, +1 results in 3
+        let input = BufReader::new("1\n1\n1\n\n".as_bytes());
+        let frequency = day_01a(input, 0, &mut Diagnostics::strict()).unwrap();
+        assert_eq!(frequency, 3);
+    }
+
+    #[test]
+    fn test_day_01_example_02() {
+        // +1, +1, -2 results in 0
+        let input = BufReader::new("1\n1\n-2\n\n".as_bytes());
+        let frequency = day_01a(input, 0, &mut Diagnostics::strict()).unwrap();
+        assert_eq!(frequency, 0);
+    }
+
+    #[test]
+    fn test_day_01_example_03() {
+        // -1, -2, -3 results in -6
+        let input = BufReader::new("-1\n-2\n-3\n\n".as_bytes());
+        let frequency = day_01a(input, 0, &mut Diagnostics::strict()).unwrap();
+        assert_eq!(frequency, -6);
+    }
+
+    #[test]
+    fn test_day_01b_example_01() {
+        // +1, -1 first reaches 0 twice.
+        let mut input = Cursor::new("1\n-1\n\n".as_bytes());
+        let repeat = day_01b(&mut input, 0, 0, &mut Diagnostics::strict()).unwrap();
+        assert_eq!(repeat.frequency, 0);
+        assert_eq!(repeat.second.pass, 0);
+    }
+
+    #[test]
+    fn test_day_01b_example_02() {
+        // +3, +3, +4, -2, -4 first reaches 10 twice.
+        // It requires the list to be read twice
+        let mut input = Cursor::new("3\n3\n4\n-2\n-4\n\n".as_bytes());
+        let repeat = day_01b(&mut input, 0, 0, &mut Diagnostics::strict()).unwrap();
+        assert_eq!(repeat.frequency, 10);
+        assert_eq!(repeat.second.pass, 1);
+    }
+
+    #[test]
+    fn test_day_01b_example_03() {
+        // -6, +3, +8, +5, -6 first reaches 5 twice.
+        // It requires the list to be read three times
+        let mut input = Cursor::new("-6\n3\n8\n5\n-6\n\n".as_bytes());
+        let repeat = day_01b(&mut input, 0, 0, &mut Diagnostics::strict()).unwrap();
+        assert_eq!(repeat.frequency, 5);
+        assert_eq!(repeat.second.pass, 2);
+    }
+
+    #[test]
+    fn test_day_01b_example_04() {
+        // +7, +7, -2, -7, -4 first reaches 14 twice.
+        // Standard in can't seek, so what's read is kept to read again
+        let mut input = input::replayable("7\n7\n-2\n-7\n-4\n\n+1\n".as_bytes());
+        let repeat = day_01b(&mut input, 0, 0, &mut Diagnostics::strict()).unwrap();
+        assert_eq!(repeat.frequency, 14);
+        assert_eq!(repeat.second.pass, 2);
+        assert_eq!(repeat, find_repeat(&[7, 7, -2, -7, -4], 0).unwrap());
+    }
+
+    #[test]
+    fn test_day_01b_rereading() {
+        let repeat = |text: &str, start_frequency| {
+            day_01b(
+                &mut Cursor::new(text.as_bytes()),
+                0,
+                start_frequency,
+                &mut Diagnostics::strict(),
+            )
+        };
+        assert_eq!(
+            repeat("+1, -2, +3, +1", 10).unwrap(),
+            find_repeat(&[1, -2, 3, 1], 10).unwrap()
+        );
+        assert_eq!(
+            repeat("1\n2\n", 0).unwrap_err().to_string(),
+            "No solution: Frequency not repeated in sequence: frequencies drift by +3 each pass and never meet"
+        );
+        assert!(repeat("\n", 0).is_err());
+
+        // Later sequences are solved on their own, as the binary does
+        let text = "+1\n-1\n\n+3\n+3\n+4\n-2\n-4\n\n";
+        assert_eq!(count_sequences(text.as_bytes()).unwrap(), 2);
+        let mut input = Cursor::new(text.as_bytes());
+        let repeat = day_01b(&mut input, 1, 0, &mut Diagnostics::strict()).unwrap();
+        assert_eq!(repeat, find_repeat(&[3, 3, 4, -2, -4], 0).unwrap());
+        assert!(day_01b(&mut input, 2, 0, &mut Diagnostics::strict()).is_err());
+
+        // Bad lines are only reported the once
+        let mut diagnostics = Diagnostics::lenient();
+        let mut input = Cursor::new("+3\nthree\n+3\n+4\n-2\n-4\n".as_bytes());
+        let repeat = day_01b(&mut input, 0, 0, &mut diagnostics).unwrap();
+        assert_eq!(repeat.frequency, 10);
+        assert_eq!(diagnostics.errors().len(), 1);
+
+        // The real input, as read from standard in
+        let file = File::open("input/day_01a/input.txt").unwrap();
+        let mut input = input::replayable(BufReader::new(file));
+        let repeat = day_01b(&mut input, 0, 0, &mut Diagnostics::strict()).unwrap();
+        assert_eq!(repeat.frequency, 481);
+        assert_eq!(repeat.steps, 146_478);
+    }
+
+    #[test]
+    fn test_day_01b_find_repeated_frequency() {
+        // The examples, then ones that take a few passes to come round,
+        // drifting up and down
+        assert_eq!(find_repeated_frequency(&[1, -1]).ok(), Some(0));
+        assert_eq!(find_repeated_frequency(&[3, 3, 4, -2, -4]).ok(), Some(10));
+        assert_eq!(find_repeated_frequency(&[-6, 3, 8, 5, -6]).ok(), Some(5));
+        assert_eq!(find_repeated_frequency(&[7, 7, -2, -7, -4]).ok(), Some(14));
+        assert_eq!(find_repeated_frequency(&[3, 4, -5]).ok(), Some(7));
+        assert_eq!(find_repeated_frequency(&[-2, 1]).ok(), Some(-2));
+        assert_eq!(find_repeated_frequency(&[2, -1]).ok(), Some(2));
+    }
+
+    #[test]
+    fn test_day_01b_find_repeat() {
+        // 0, 1, -1, 2, then 3, 4, 2 on the second pass
+        let repeat = find_repeat(&[1, -2, 3, 1], 0).unwrap();
+        assert_eq!(repeat.frequency, 2);
+        assert_eq!(repeat.steps, 6);
+        assert_eq!(
+            repeat.first,
+            Occurrence {
+                step: 3,
+                pass: 0,
+                delta_index: Some(2)
+            }
+        );
+        assert_eq!(repeat.second.to_string(), "after step 6 (pass 1, delta 1)");
+
+        // Back to the start at the end of the first pass
+        let repeat = find_repeat(&[1, -1], 0).unwrap();
+        assert_eq!(repeat.first.to_string(), "at the start");
+        assert_eq!(repeat.second, Occurrence::at(2, 2));
+        assert_eq!(repeat.second.delta_index, Some(1));
+    }
+
+    #[test]
+    fn test_day_01b_never_repeated() {
+        // Nothing to loop over, or every frequency drifts off somewhere new
+        let drifts_apart = |drift| Err(FrequencyNotRepeated::DriftsApart { drift });
+        assert_eq!(
+            find_repeated_frequency(&[]),
+            Err(FrequencyNotRepeated::NoDeltas)
+        );
+        assert_eq!(find_repeated_frequency(&[1]), drifts_apart(1));
+        assert_eq!(find_repeated_frequency(&[1, 2, -1, 5]), drifts_apart(7));
+        assert_eq!(find_repeated_frequency(&[-10, 1]), drifts_apart(-9));
+
+        // Turned away up front rather than looping forever
+        assert_eq!(
+            process_frequency_deltas(&[1, 2, -1, 5], 0, &mut HashSet::new(), &Budget::default())
+                .map_err(|e| e.to_string()),
+            Err(Error::from(FrequencyNotRepeated::DriftsApart { drift: 7 }).to_string())
+        );
+        assert_eq!(
+            check_repeats::<i32>(&[]).map_err(|e| e.to_string()),
+            Err(Error::from(FrequencyNotRepeated::<i32>::NoDeltas).to_string())
+        );
+        assert!(check_repeats(&[3, 4, -5]).is_ok());
+
+        // A first pass too big to add up can't be shown to repeat
+        match check_repeats(&[1, i32::MAX, -5]) {
+            Err(Error::Overflow { line, text, .. }) => {
+                assert_eq!(line, 2);
+                assert_eq!(text, "+2147483647");
+            }
+            other => panic!("Expected an overflow, got {:?}", other),
+        }
+        assert!(process_frequency_deltas(
+            &[1, i32::MAX, -5],
+            0,
+            &mut HashSet::new(),
+            &Budget::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_day_01b_budget() {
+        // 5 takes 12 steps to come round again
+        let deltas = [-6, 3, 8, 5, -6];
+        let run =
+            |budget: Budget| process_frequency_deltas(&deltas, 0, &mut HashSet::new(), &budget);
+
+        let steps = |most| Budget {
+            steps: Some(most),
+            time: None,
+        };
+        assert_eq!(run(steps(12)).ok(), Some(5));
+        match run(steps(11)) {
+            Err(Error::NoSolution(reason)) => assert!(reason.contains("gave up after 11 steps")),
+            other => panic!("expected to run out of budget, got {:?}", other),
+        }
+
+        // No time at all still gets one pass in
+        let no_time = Budget {
+            steps: None,
+            time: Some(Duration::from_secs(0)),
+        };
+        assert!(match run(no_time) {
+            Err(Error::NoSolution(reason)) => reason.contains("gave up after 5 steps"),
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn test_day_01b_matches_looping() {
+        // Small made up inputs, checked against looping until a repeat
+        let mut seed: u32 = 1;
+        let mut next = |range: i32| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 16) as i32 % range
+        };
+
+        for _ in 0..500 {
+            let length = 1 + next(8) as usize;
+            let deltas: Vec<i32> = (0..length).map(|_| next(21) - 10).collect();
+
+            if let Ok(repeat) = find_repeat(&deltas, 0) {
+                let mut seen_frequencies = HashSet::new();
+                assert_eq!(
+                    process_frequency_deltas(&deltas, 0, &mut seen_frequencies, &Budget::default())
+                        .ok(),
+                    Some(repeat.frequency),
+                    "{:?}",
+                    deltas
+                );
+
+                // Every frequency up to the repeat was new
+                assert_eq!(seen_frequencies.len() as u64, repeat.steps, "{:?}", deltas);
+
+                // and all within the range worked out for the bitmap
+                let mut bitmap = match SeenStore::new(&deltas, 0, SeenChoice::Bitmap) {
+                    SeenStore::Bitmap(bitmap) => bitmap,
+                    SeenStore::Hash(_) => panic!("asked for a bitmap"),
+                };
+                assert_eq!(
+                    process_frequency_deltas(&deltas, 0, &mut bitmap, &Budget::default()).ok(),
+                    Some(repeat.frequency)
+                );
+                assert_eq!(bitmap.len() as u64, repeat.steps);
+                assert!(bitmap.outside.is_empty(), "{:?}", deltas);
+                let frequency_after =
+                    |step: u64| deltas.iter().cycle().take(step as usize).sum::<i32>();
+                assert_eq!(frequency_after(repeat.first.step), repeat.frequency);
+                assert_eq!(frequency_after(repeat.second.step), repeat.frequency);
+
+                // The second visit of anything is the first repeat
+                assert_eq!(
+                    nth_visit(&deltas, 0, 2, &Budget::default()),
+                    Ok((i64::from(repeat.frequency), repeat.second)),
+                    "{:?}",
+                    deltas
+                );
+            } else {
+                assert_eq!(most_visits(&deltas), Some(1), "{:?}", deltas);
+            }
+
+            // Reading the input again each pass comes to the same
+            let text: Vec<String> = deltas.iter().map(i32::to_string).collect();
+            let mut input = Cursor::new(text.join("\n"));
+            let reread = day_01b(&mut input, 0, 0, &mut Diagnostics::strict());
+            assert_eq!(reread.ok(), find_repeat(&deltas, 0).ok(), "{:?}", deltas);
+        }
+    }
+
+    #[test]
+    fn test_day_01b_seen_stores() {
+        // 0, 1, -1, 2, then on by 3 a pass until 2 comes round again
+        assert_eq!(reachable_range(&[1, -2, 3, 1], 0), (-1, 6));
+        assert_eq!(reachable_range(&[1, -2, 3, 1], 10), (9, 16));
+        assert_eq!(reachable_range(&[-1, 2, -3, -1], 0), (-6, 1));
+        assert_eq!(reachable_range(&[5, -5], 0), (0, 5));
+
+        let mut bitmap = FrequencyBitmap::new(-3, 70);
+        for frequency in &[-3, 0, 64, 70] {
+            assert!(bitmap.insert(*frequency));
+            assert!(!bitmap.insert(*frequency));
+        }
+        // Out of range still works, just not in the bitmap
+        assert!(bitmap.insert(1000));
+        assert!(!bitmap.insert(1000));
+        assert!(bitmap.insert(-4));
+        assert_eq!(bitmap.len(), 6);
+
+        let store = |choice| match SeenStore::new(&[1, -2, 3, 1], 0, choice) {
+            SeenStore::Bitmap(_) => "bitmap",
+            SeenStore::Hash(_) => "hash",
+        };
+        assert_eq!(store(SeenChoice::Auto), "bitmap");
+        assert_eq!(store(SeenChoice::Hash), "hash");
+        match SeenStore::new(&[i32::MAX, i32::MAX, -1], 0, SeenChoice::Auto) {
+            SeenStore::Hash(_) => (),
+            SeenStore::Bitmap(_) => panic!("range too wide for a bitmap"),
+        }
+
+        for choice in &[SeenChoice::Auto, SeenChoice::Bitmap, SeenChoice::Hash] {
+            let mut seen = SeenStore::new(&[1, -2, 3, 1], 0, *choice);
+            let repeat = process_frequency_deltas(&[1, -2, 3, 1], 0, &mut seen, &Budget::default());
+            assert_eq!(repeat.ok(), Some(2));
+            assert_eq!(seen.len(), 6);
+        }
+    }
+
+    #[test]
+    fn test_day_01b_repeat_statistics() {
+        // 0, 1, 0, 1, 0, ... round and round
+        let deltas = [1, -1];
+        assert_eq!(most_visits(&deltas), None);
+        assert_eq!(
+            nth_visit(&deltas, 0, 3, &Budget::default()),
+            Ok((0, Occurrence::at(4, 2)))
+        );
+        assert_eq!(
+            nth_visit(&deltas, 7, 1, &Budget::default()),
+            Ok((7, Occurrence::at(0, 2)))
+        );
+        assert_eq!(
+            repeats_within(&deltas, 0, 2),
+            vec![(0, Occurrence::at(2, 2)), (1, Occurrence::at(3, 2))]
+        );
+        let histogram: Vec<(u64, usize)> = visit_histogram(&deltas, 0, 2).into_iter().collect();
+        assert_eq!(histogram, vec![(2, 1), (3, 1)]);
+
+        // 0, 1, -1, 2, then on by 3 a pass; -1 and 2 share a remainder
+        let deltas = [1, -2, 3, 1];
+        assert_eq!(most_visits(&deltas), Some(2));
+        assert_eq!(
+            nth_visit(&deltas, 0, 3, &Budget::default()),
+            Err(FrequencyNotRepeated::TooFewVisits { visits: 3, most: 2 })
+        );
+        assert_eq!(repeats_within(&deltas, 0, 1), vec![]);
+        assert_eq!(
+            repeats_within(&deltas, 0, 2),
+            vec![(2, Occurrence::at(6, 4))]
+        );
+        let histogram: Vec<(u64, usize)> = visit_histogram(&deltas, 0, 3).into_iter().collect();
+        assert_eq!(histogram, vec![(1, 9), (2, 2)]);
+
+        assert_eq!(
+            nth_visit(&[], 0, 2, &Budget::default()),
+            Err(FrequencyNotRepeated::NoDeltas)
+        );
+        match nth_visit(
+            &[1, -1],
+            0,
+            1000,
+            &Budget {
+                steps: Some(10),
+                time: None,
+            },
+        ) {
+            Err(FrequencyNotRepeated::OutOfBudget { steps, .. }) => assert_eq!(steps, 10),
+            other => panic!("expected to run out of budget, got {:?}", other),
+        }
+
+        let args = |args: &[&str]| day_args(args, &["visits", "cycles"]);
+        assert_eq!(stats_options(&args(&[])), Ok(None));
+        assert_eq!(
+            stats_options(&args(&["stats", "--cycles", "5"])),
+            Ok(Some(StatsOptions {
+                visits: 3,
+                cycles: 5
+            }))
+        );
+        assert!(stats_options(&args(&["--visits", "4"])).is_err());
+        assert!(stats_options(&args(&["stats", "--format", "json"])).is_err());
+    }
+
+    #[test]
+    fn test_day_01_seen_option() {
+        let args = |args: &[&str]| day_args(args, &["seen"]);
+        assert_eq!(seen_option(&args(&[])), Ok(None));
+        assert_eq!(
+            seen_option(&args(&["--seen", "bitmap", "input.txt"])),
+            Ok(Some(SeenChoice::Bitmap))
+        );
+        assert_eq!(
+            seen_option(&args(&["--seen=hash"])),
+            Ok(Some(SeenChoice::Hash))
+        );
+        assert_eq!(
+            seen_option(&args(&["--seen", "tree"])),
+            Err("--seen has an invalid value 'tree'".to_string())
+        );
+        assert_eq!(
+            seen_option(&args(&["stats", "--seen", "auto"])),
+            Err("--seen doesn't work with stats".to_string())
+        );
+        assert!(seen_option(&args(&["--seen", "auto", "--format", "json"])).is_err());
+    }
+
+    #[test]
+    fn test_day_01_solution() {
+        let mut diagnostics = Diagnostics::strict();
+        let frequency_deltas = Day01::parse("+1\n-2\n+3\n+1\n".as_bytes(), &mut diagnostics);
+        let frequency_deltas = frequency_deltas.unwrap();
+        assert_eq!(frequency_deltas, vec![1, -2, 3, 1]);
+        assert_eq!(Day01::part1(&frequency_deltas).ok(), Some(3));
+        assert_eq!(Day01::part2(&frequency_deltas).ok(), Some(2));
+    }
+
+    #[test]
+    fn test_day_01_bad_line() {
+        let input = "+1\nplus two\n+3\n";
+
+        let mut diagnostics = Diagnostics::strict();
+        match Day01::parse(input.as_bytes(), &mut diagnostics) {
+            Err(Error::Parse { line, text, .. }) => {
+                assert_eq!(line, 2);
+                assert_eq!(text, "plus two");
+            }
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+
+        let mut diagnostics = Diagnostics::new(Mode::Lenient);
+        let frequency_deltas = Day01::parse(input.as_bytes(), &mut diagnostics).unwrap();
+        assert_eq!(frequency_deltas, vec![1, 3]);
+        assert_eq!(diagnostics.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_day_01_puzzle_formats() {
+        // Pasted straight from the puzzle, one example per sequence
+        let input = "\n# Part 1 examples\n+1, +1, +1\n\n+1, +1, -2\n\n\n-1 -2\t-3 # no commas\n";
+        let mut diagnostics = Diagnostics::strict();
+        let sequences =
+            read_frequency_sequences::<i32, _>(input.as_bytes(), &mut diagnostics).unwrap();
+
+        assert_eq!(sequences.len(), 3);
+        assert_eq!(
+            sequences[2],
+            Sequence {
+                deltas: vec![-1, -2, -3],
+                lines: vec![8, 8, 8],
+            }
+        );
+        let totals: Vec<i32> = sequences
+            .iter()
+            .map(|sequence| sum_frequency_deltas(sequence, 0).unwrap())
+            .collect();
+        assert_eq!(totals, vec![3, 0, -6]);
+
+        // The first sequence alone, for the solution
+        let frequency_deltas = Day01::parse(input.as_bytes(), &mut diagnostics).unwrap();
+        assert_eq!(frequency_deltas, vec![1, 1, 1]);
+
+        // A heading on its own is no sequence, and a note doesn't end one
+        let input = "# Part 1 examples\n\n+1, +1, +1\n# then\n+2\n\n# done\n";
+        let sequences =
+            read_frequency_sequences::<i32, _>(input.as_bytes(), &mut diagnostics).unwrap();
+        assert_eq!(sequences.len(), 1);
+        assert_eq!(sequences[0].deltas, vec![1, 1, 1, 2]);
+        let frequency_deltas = Day01::parse(input.as_bytes(), &mut diagnostics).unwrap();
+        assert_eq!(frequency_deltas, vec![1, 1, 1, 2]);
+
+        // Which is all JSON output can answer for
+        assert!(single_sequence_input(input.as_bytes()).is_ok());
+        assert_eq!(
+            single_sequence_input("+1\n\n+2\n".as_bytes()),
+            Err("--format json solves one sequence, but the input has 2".to_string())
+        );
+        let frequency = day_01a("+1, -2, +3, +1\n\n+5\n".as_bytes(), 0, &mut diagnostics);
+        assert_eq!(frequency.ok(), Some(3));
+    }
+
+    #[test]
+    fn test_day_01_frequency_stream() {
+        let input = "+1, -2\n\nbad\n+3\n-2\n+5\n".as_bytes();
+        let events: Vec<Result<FrequencyEvent<i32>>> =
+            frequency_stream(input, 0).watch_repeats().collect();
+
+        let event = |index: usize| events[index].as_ref().ok().cloned();
+        let changed = |delta, frequency, line| {
+            Some(FrequencyEvent::Changed {
+                delta,
+                frequency,
+                line,
+            })
+        };
+        assert_eq!(event(0), changed(1, 1, 1));
+        assert_eq!(event(1), changed(-2, -1, 1));
+        assert_eq!(
+            events[2].as_ref().unwrap_err().to_string(),
+            "line 3, column 1: invalid digit found in string ('bad')"
+        );
+        assert_eq!(event(3), changed(3, 2, 4));
+        assert_eq!(event(4), changed(-2, 0, 5));
+        assert_eq!(
+            event(5),
+            Some(FrequencyEvent::FirstRepeat {
+                frequency: 0,
+                line: 5,
+                step: 4,
+            })
+        );
+        // Only the first repeat is given
+        assert_eq!(event(6), changed(5, 5, 6));
+        assert_eq!(events.len(), 7);
+
+        // Stops at the total that doesn't fit
+        let mut stream = frequency_stream("+1\n+1\n+1\n".as_bytes(), i32::MAX - 1);
+        assert!(stream.next().unwrap().is_ok());
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+        assert_eq!(*stream.frequency(), i32::MAX);
+
+        let args = |args: &[&str]| day_args(args, &[]);
+        assert!(check_stream_format(&args(&["stream"])).is_ok());
+        assert!(check_stream_format(&args(&["--format", "json"])).is_ok());
+        assert!(check_stream_format(&args(&["stream", "--format", "json"])).is_err());
+    }
+
+    #[test]
+    fn test_day_01_stream_across_buffers() {
+        // Deltas cut in two by the end of a buffer are put back together
+        let input = "+10, -200\n+3000 # nöte\n +x4\n-5";
+        for capacity in 1..8 {
+            let handle = BufReader::with_capacity(capacity, input.as_bytes());
+            let mut stream = frequency_stream(handle, 0);
+            let events: Vec<Result<FrequencyEvent<i32>>> = stream.by_ref().collect();
+            assert_eq!(events.len(), 5, "capacity {}", capacity);
+            assert_eq!(
+                events[3].as_ref().err().map(Error::to_string),
+                Some("line 3, column 2: invalid digit found in string ('+x4')".to_string())
+            );
+            assert_eq!(*stream.frequency(), 2805);
+        }
+    }
+
+    #[test]
+    fn test_day_01_endless_stream() {
+        // Input that never ends, as from a pipe
+        struct Endless(&'static [u8], usize);
+        impl io::Read for Endless {
+            fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+                for byte in buffer.iter_mut() {
+                    *byte = self.0[self.1 % self.0.len()];
+                    self.1 += 1;
+                }
+                Ok(buffer.len())
+            }
+        }
+
+        let mut stream = frequency_stream(BufReader::new(Endless(b"+1\n+2\n", 0)), 0i64);
+        let taken = stream.by_ref().take(100_000).filter(Result::is_ok).count();
+        assert_eq!(taken, 100_000);
+        assert_eq!(*stream.frequency(), 150_000);
+
+        let mut stream = frequency_stream(BufReader::new(Endless(b"+1\n-1\n", 0)), 0);
+        let repeat = stream.by_ref().take(10).position(|event| match event {
+            Ok(FrequencyEvent::FirstRepeat { frequency, .. }) => frequency == 0,
+            _ => false,
+        });
+        assert_eq!(repeat, None);
+        let mut stream =
+            frequency_stream(BufReader::new(Endless(b"+1\n-1\n", 0)), 0).watch_repeats();
+        let repeat = stream.by_ref().take(10).position(|event| match event {
+            Ok(FrequencyEvent::FirstRepeat { frequency, .. }) => frequency == 0,
+            _ => false,
+        });
+        assert_eq!(repeat, Some(2));
+        assert!(stream.seen_frequencies.is_none());
+
+        // One line that never ends still gives totals as it goes
+        let mut stream = frequency_stream(BufReader::new(Endless(b"+1, -2,+3 ", 0)), 0i64);
+        let last = stream.by_ref().take(30_000).last();
+        assert!(match last {
+            Some(Ok(FrequencyEvent::Changed { line, .. })) => line == 1,
+            _ => false,
+        });
+        assert_eq!(*stream.frequency(), 20_000);
+        assert!(stream.scanner.carry.capacity() < 16);
+    }
+
+    #[test]
+    fn test_day_01_bad_delta_column() {
+        let mut diagnostics = Diagnostics::lenient();
+        let sequences = read_frequency_sequences::<i32, _>(
+            "+1, +2\n+3, +x4, +5\n\n+6\n".as_bytes(),
+            &mut diagnostics,
+        )
+        .unwrap();
+
+        // The whole line is skipped, and sequences after it still count lines
+        assert_eq!(sequences[0].deltas, vec![1, 2]);
+        assert_eq!(sequences[1].lines, vec![4]);
+        assert_eq!(
+            diagnostics.errors()[0].to_string(),
+            "line 2, column 5: invalid digit found in string ('+3, +x4, +5')"
+        );
+
+        // Overflow still points at the right line in a later sequence
+        let sequences = read_frequency_sequences::<i32, _>(
+            "+1\n\n+2147483647\n+1\n".as_bytes(),
+            &mut diagnostics,
+        )
+        .unwrap();
+        match sum_frequency_deltas(&sequences[1], 0) {
+            Err(Error::Overflow { line, .. }) => assert_eq!(line, 4),
+            other => panic!("Expected an overflow, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_day_01_start_frequency() {
+        let mut diagnostics = Diagnostics::strict();
+        let frequency = day_01a("+1\n-2\n+3\n+1\n".as_bytes(), 10, &mut diagnostics);
+        assert_eq!(frequency.ok(), Some(13));
+
+        // Everything moves up by the start, when it happens does not
+        let repeat = find_repeat(&[1, -2, 3, 1], 10).unwrap();
+        assert_eq!((repeat.frequency, repeat.steps), (12, 6));
+        assert_eq!(find_repeat(&[1, -1], -4).unwrap().frequency, -4);
+        assert_eq!(
+            process_frequency_deltas(&[1, -2, 3, 1], 10, &mut HashSet::new(), &Budget::default())
+                .ok(),
+            Some(12)
+        );
+
+        let args = |args: &[&str]| day_args(args, &["start"]);
+        assert_eq!(start_option(&args(&[])), Ok(0));
+        assert_eq!(start_option(&args(&["--start", "-7"])), Ok(-7));
+        assert!(start_option(&args(&["--start", "-7", "--format", "json"])).is_err());
+
+        // Only the answer has to fit
+        let repeat = find_repeat(&[1, -1], i32::MAX);
+        assert_eq!(repeat.unwrap().frequency, i32::MAX);
+        assert_eq!(
+            find_repeat(&[3, -2], i32::MAX - 1),
+            Err(FrequencyNotRepeated::Overflow(Occurrence::at(6, 2)))
+        );
+    }
+
+    #[test]
+    fn test_day_01_overflow() {
+        // Bad lines skipped in lenient mode still count towards the line
+        let input = "+2147483000\nbad\n+647\n+1\n-5\n";
+
+        let mut diagnostics = Diagnostics::lenient();
+        match day_01a::<i32, _>(input.as_bytes(), 0, &mut diagnostics) {
+            Err(Error::Overflow { line, text, .. }) => {
+                assert_eq!(line, 4);
+                assert_eq!(text, "+1");
+            }
+            other => panic!("Expected an overflow, got {:?}", other),
+        }
+
+        let mut diagnostics = Diagnostics::lenient();
+        let mut frequency_deltas: Vec<i32> = Vec::new();
+        let error = read_frequency_deltas(
+            &mut input.as_bytes(),
+            &mut frequency_deltas,
+            &mut diagnostics,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 4: frequency 2147483647 +1 overflows i32 ('+1')"
+        );
+
+        // Wider types have room
+        let mut diagnostics = Diagnostics::lenient();
+        let frequency = day_01a::<i64, _>(input.as_bytes(), 0, &mut diagnostics);
+        assert_eq!(frequency.ok(), Some(2_147_483_643));
+    }
+
+    #[test]
+    fn test_day_01_big_frequencies() {
+        let input = "+170141183460469231731687303715884105727\n+1\n+1\n";
+        let mut diagnostics = Diagnostics::strict();
+
+        let error = day_01a::<i128, _>(input.as_bytes(), 0, &mut diagnostics).unwrap_err();
+        assert!(error.to_string().starts_with("line 2: "));
+
+        let frequency = day_01a::<BigInt, _>(input.as_bytes(), BigInt::zero(), &mut diagnostics);
+        assert_eq!(
+            frequency.unwrap().to_string(),
+            "170141183460469231731687303715884105729"
+        );
+
+        // Generic looping agrees with the examples, whatever the type
+        let deltas: Vec<BigInt> = [7, 7, -2, -7, -4]
+            .iter()
+            .map(|d| BigInt::from(*d as i64))
+            .collect();
+        assert_eq!(
+            process_frequency_deltas(
+                &deltas,
+                BigInt::zero(),
+                &mut HashSet::new(),
+                &Budget::default()
+            )
+            .ok(),
+            Some(BigInt::from(14i64))
+        );
+        let deltas: Vec<BigInt> = [1, 2, -1, 5]
+            .iter()
+            .map(|d| BigInt::from(*d as i64))
+            .collect();
+        assert_eq!(
+            check_repeats(&deltas).map_err(|e| e.to_string()),
+            Err(Error::from(FrequencyNotRepeated::DriftsApart {
+                drift: BigInt::from(7i64)
+            })
+            .to_string())
+        );
+    }
+
+    #[test]
+    fn test_day_01b_overflow() {
+        // Could repeat, but runs out of room on the second pass first
+        assert_eq!(
+            process_frequency_deltas(
+                &[i32::MAX, -2_147_483_646],
+                0,
+                &mut HashSet::new(),
+                &Budget::default()
+            )
+            .map_err(|e| e.to_string()),
+            Err(
+                Error::from(FrequencyNotRepeated::<i32>::Overflow(Occurrence::at(3, 2)))
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_day_01_no_deltas() {
+        assert!(Day01::part2(&Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_day_01_line_endings() {
+        // Windows line endings, a byte order mark, trailing spaces and no
+        // newline on the last line should all read the same as the plain file
+        for input in &[
+            "+1\n-2\n+3\n+1\n",
+            "+1\r\n-2\r\n+3\r\n+1\r\n",
+            "+1\n-2\r\n+3\n+1",
+            "\u{feff}+1\r\n-2 \r\n+3\t\n+1",
+        ] {
+            let mut diagnostics = Diagnostics::strict();
+            let frequency_deltas = Day01::parse(input.as_bytes(), &mut diagnostics).unwrap();
+            assert_eq!(frequency_deltas, vec![1, -2, 3, 1]);
+
+            let frequency = day_01a(input.as_bytes(), 0, &mut diagnostics).unwrap();
+            assert_eq!(frequency, 3);
+        }
+    }
+}
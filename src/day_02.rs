@@ -0,0 +1,475 @@
+//! Day 2: Inventory Management System
+//!
+//! https://adventofcode.com/2018/day/2
+
+use cli::{Args, DayOptions};
+use error::{Diagnostics, Error, Result};
+use input;
+use solution::Solution;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use trace::{self, Level};
+
+const GRAM_RANGE: usize = (1 + GRAM_RANGE_END - GRAM_RANGE_START) as usize;
+const GRAM_RANGE_START: u8 = b'a';
+const GRAM_RANGE_END: u8 = b'z';
+
+/// How many times each letter turns up in `box_id`. Anything but a
+/// lowercase letter is left out.
+fn letter_counts(box_id: &str) -> [u32; GRAM_RANGE] {
+    let mut seen = [0; GRAM_RANGE];
+    for byte in box_id.bytes() {
+        if let GRAM_RANGE_START..=GRAM_RANGE_END = byte {
+            seen[(byte - GRAM_RANGE_START) as usize] += 1;
+        }
+    }
+    seen
+}
+
+/// Part 1: how many box IDs have some letter exactly twice, and exactly
+/// three times, multiplied together.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Checksum {
+    pub twice: u64,
+    pub thrice: u64,
+    pub product: u64,
+}
+
+/// What the checksum multiplies together.
+const CHECKSUM_MULTIPLICITIES: &[u32] = &[2, 3];
+
+/// The checksum of box IDs as read by `read_box_ids`.
+pub fn checksum<S: AsRef<str>>(box_ids: &[S]) -> Result<Checksum> {
+    checksum_of(&count_multiplicities(box_ids, CHECKSUM_MULTIPLICITIES))
+}
+
+fn checksum_of(counted: &Multiplicities) -> Result<Checksum> {
+    let product = counted
+        .product(CHECKSUM_MULTIPLICITIES)
+        .ok_or_else(|| Error::NoSolution("the checksum is too big for 64 bits".to_string()))?;
+    Ok(Checksum {
+        twice: counted.count(2),
+        thrice: counted.count(3),
+        product,
+    })
+}
+
+/// How many times the letters of `box_id` turn up, each count once and in
+/// order, e.g. `[1, 2, 3]` for `bababc`.
+pub fn signature(box_id: &str) -> Vec<u32> {
+    let mut signature: Vec<u32> = letter_counts(box_id)
+        .iter()
+        .cloned()
+        .filter(|count| *count > 0)
+        .collect();
+    signature.sort();
+    signature.dedup();
+    signature
+}
+
+/// How many box IDs have some letter exactly so many times, for each
+/// multiplicity asked about.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Multiplicities {
+    pub counts: BTreeMap<u32, u64>,
+}
+
+impl Multiplicities {
+    /// Nothing counted yet for each of `multiplicities`.
+    fn new(multiplicities: &[u32]) -> Multiplicities {
+        Multiplicities {
+            counts: multiplicities.iter().map(|m| (*m, 0)).collect(),
+        }
+    }
+
+    /// Count one more box ID with these letter counts.
+    fn add(&mut self, seen: &[u32; GRAM_RANGE]) {
+        for (multiplicity, count) in self.counts.iter_mut() {
+            if seen.contains(multiplicity) {
+                *count += 1;
+            }
+        }
+    }
+
+    /// Box IDs with a letter exactly `multiplicity` times, or 0 if it
+    /// wasn't asked about.
+    pub fn count(&self, multiplicity: u32) -> u64 {
+        self.counts.get(&multiplicity).cloned().unwrap_or(0)
+    }
+
+    /// The counts for `chosen` multiplied together, as the checksum does for
+    /// 2 and 3. `None` if one wasn't asked about, or it's too big.
+    pub fn product(&self, chosen: &[u32]) -> Option<u64> {
+        chosen.iter().try_fold(1u64, |product, multiplicity| {
+            product.checked_mul(*self.counts.get(multiplicity)?)
+        })
+    }
+}
+
+/// Count box IDs with a letter exactly each of `multiplicities` times.
+pub fn count_multiplicities<S: AsRef<str>>(
+    box_ids: &[S],
+    multiplicities: &[u32],
+) -> Multiplicities {
+    let mut counted = Multiplicities::new(multiplicities);
+    for box_id in box_ids {
+        counted.add(&letter_counts(box_id.as_ref()));
+    }
+    counted
+}
+
+/// What `day_02a multiplicities` looks for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultiplicityOptions {
+    /// Count box IDs with a letter this many times, for each of these.
+    pub counts: Vec<u32>,
+    /// Multiply the counts for these together.
+    pub product: Vec<u32>,
+}
+
+/// A list of multiplicities such as `2,3,4`.
+fn parse_multiplicities(name: &str, text: &str) -> ::std::result::Result<Vec<u32>, String> {
+    text.split(',')
+        .map(|multiplicity| match multiplicity.trim().parse::<u32>() {
+            Ok(0) | Err(_) => Err(format!(
+                "--{} takes multiplicities from 1 up, such as 2,3,4, not '{}'",
+                name, text
+            )),
+            Ok(multiplicity) => Ok(multiplicity),
+        })
+        .collect()
+}
+
+/// `--counts` (2,3 if not given) and `--product` (all of the counts) for
+/// `day_02a multiplicities`, or `None` when not asked for them. Any in the
+/// product are counted too. There's no JSON report of them.
+pub fn multiplicity_options(
+    args: &Args,
+) -> ::std::result::Result<Option<MultiplicityOptions>, String> {
+    let counts = args
+        .option("counts")
+        .map(|text| parse_multiplicities("counts", text));
+    let product = args
+        .option("product")
+        .map(|text| parse_multiplicities("product", text));
+
+    if args.positional(0) != Some("multiplicities") {
+        return match (counts, product) {
+            (None, None) => Ok(None),
+            _ => Err("--counts and --product only work with multiplicities".to_string()),
+        };
+    }
+    DayOptions::require_text(args, "multiplicities")?;
+
+    let mut counts = counts.unwrap_or_else(|| Ok(vec![2, 3]))?;
+    let product = product.unwrap_or_else(|| Ok(counts.clone()))?;
+    counts.extend(&product);
+    counts.sort();
+    counts.dedup();
+
+    Ok(Some(MultiplicityOptions { counts, product }))
+}
+
+/// The workings of `checksum` as CSV: a row per box ID with its letter
+/// counts, the twice and thrice tallies so far, and the ID itself.
+pub fn write_checksum_csv<S: AsRef<str>, W: Write>(box_ids: &[S], out: &mut W) -> io::Result<()> {
+    let mut header = String::default();
+    for letter in GRAM_RANGE_START..=GRAM_RANGE_END {
+        header += &format!("{},", letter as char);
+    }
+    writeln!(out, "{}twice,thrice,line", header)?;
+
+    let mut counted = Multiplicities::new(CHECKSUM_MULTIPLICITIES);
+    for box_id in box_ids {
+        let seen = letter_counts(box_id.as_ref());
+        counted.add(&seen);
+
+        let mut counts = String::default();
+        for count in &seen {
+            counts += &format!("{},", count);
+        }
+        writeln!(
+            out,
+            "{}{:05},{:06},{}",
+            counts,
+            counted.count(2),
+            counted.count(3),
+            box_id.as_ref()
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Check a box ID is only made of lowercase letters.
+fn parse_box_id(line_number: usize, line: String) -> Result<String> {
+    match line.find(|c: char| !c.is_ascii_lowercase()) {
+        Some(column) => Err(Error::parse(
+            line_number,
+            column + 1,
+            &line,
+            "box IDs are lowercase letters only",
+        )),
+        None => Ok(line),
+    }
+}
+
+/// Read one box ID per line into `box_ids`.
+/// IDs with anything but lowercase letters go to `diagnostics`.
+pub fn read_box_ids<T: BufRead>(
+    read_handle: &mut T,
+    box_ids: &mut Vec<String>,
+    diagnostics: &mut Diagnostics,
+) -> Result<()> {
+    for (index, line) in input::lines(read_handle).enumerate() {
+        if let Some(box_id) = diagnostics.check(parse_box_id(index + 1, line?))? {
+            box_ids.push(box_id);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn process_box_ids(box_ids: &[String]) -> Result<String> {
+    // So this algorithm stinks O (n^2) because of the nested processing of data
+    // Might also do a lot of allocations?
+    // ... but the input.txt only has 250 lines so... *shrugs*
+    for (lhs_index, lhs_id) in box_ids.iter().enumerate() {
+        // Using the for loop to give us a starting point for the next search
+        // We don't need to compare anything that has been on the left side again
+        for rhs_id in box_ids.iter().skip(lhs_index + 1) {
+            if lhs_id
+                .chars() // all the characters
+                .zip(rhs_id.chars()) // iterates over rhs_id at the same time as lhs_id
+                .filter(|(a, b)| a != b) // find the inequal characters
+                .count()
+                == 1
+            {
+                trace::trace(format_args!(
+                    "  - {} and {} differ by one character",
+                    lhs_id, rhs_id
+                ));
+
+                // if there is only one inequal character
+                return Ok(lhs_id
+                    .chars() // Get all the characters
+                    .zip(rhs_id.chars()) // .. of both left and right
+                    .filter(|(a, b)| a == b) // Only pick ones that are equal
+                    .map(|(a, _)| a) // and return them. Map here maintains order.
+                    .collect()); // Collect joins the characters up again without the non-matching character
+            }
+        }
+    }
+
+    // Assuming the data is correct this point should never be reached
+    Err(Error::NoSolution(
+        "no two box IDs differ by exactly one character".to_string(),
+    ))
+}
+
+/// Part 1 is the box ID checksum, part 2 the letters common to the two
+/// IDs that differ by one character.
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<String>;
+    type Answer1 = u64;
+    type Answer2 = String;
+
+    fn parse<T: BufRead>(mut handle: T, diagnostics: &mut Diagnostics) -> Result<Vec<String>> {
+        let mut box_ids = Vec::new();
+        read_box_ids(&mut handle, &mut box_ids, diagnostics)?;
+        Ok(box_ids)
+    }
+
+    fn part1(box_ids: &Vec<String>) -> Result<u64> {
+        // The CSV is only put together when it will be shown
+        if trace::enabled(Level::Trace) {
+            let mut csv = Vec::new();
+            write_checksum_csv(box_ids, &mut csv)?;
+            for row in String::from_utf8_lossy(&csv).lines() {
+                trace::trace(format_args!("{}", row));
+            }
+        }
+
+        let checksum = checksum(box_ids)?;
+        trace::trace(format_args!(
+            "twice {}   thrice {}",
+            checksum.twice, checksum.thrice
+        ));
+        Ok(checksum.product)
+    }
+
+    fn part2(box_ids: &Vec<String>) -> Result<String> {
+        process_box_ids(box_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use cli::day_args;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_day_02a_example_01() {
+        // Example taken from in exercise description
+        let mut input_handle =
+            Cursor::new("abcdef\nbababc\nabbcde\nabcccd\naabcdd\nabcdee\nababab\n\n".as_bytes());
+        //                abcdef  bababc  abbcde  abcccd  aabcdd  abcdee  ababab
+        let mut box_ids = Vec::new();
+        read_box_ids(&mut input_handle, &mut box_ids, &mut Diagnostics::strict()).unwrap();
+
+        assert_eq!(
+            checksum(&box_ids).unwrap(),
+            Checksum {
+                twice: 4,
+                thrice: 3,
+                product: 12
+            }
+        );
+    }
+
+    #[test]
+    fn test_day_02a_checksum_too_big() {
+        let mut counted = Multiplicities::new(CHECKSUM_MULTIPLICITIES);
+        counted.counts.insert(2, u64::MAX / 2);
+        counted.counts.insert(3, 3);
+        let error = checksum_of(&counted).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "No solution: the checksum is too big for 64 bits"
+        );
+    }
+
+    #[test]
+    fn test_day_02a_checksum_csv() {
+        let mut csv = Vec::new();
+        write_checksum_csv(&["abcdef", "bababc"], &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].starts_with("a,b,c,"));
+        assert!(rows[0].ends_with(",y,z,twice,thrice,line"));
+        assert!(rows[1].ends_with(",0,00000,000000,abcdef"));
+        assert!(rows[2].starts_with("2,3,1,0,"));
+        assert!(rows[2].ends_with(",0,00001,000001,bababc"));
+    }
+
+    #[test]
+    fn test_day_02a_multiplicities() {
+        let box_ids = [
+            "abcdef", "bababc", "abbcde", "abcccd", "aabcdd", "abcdee", "ababab",
+        ];
+        let signatures: Vec<Vec<u32>> = box_ids.iter().map(|id| signature(id)).collect();
+        assert_eq!(
+            signatures,
+            vec![
+                vec![1],
+                vec![1, 2, 3],
+                vec![1, 2],
+                vec![1, 3],
+                vec![1, 2],
+                vec![1, 2],
+                vec![3],
+            ]
+        );
+
+        let counted = count_multiplicities(&box_ids, &[1, 2, 3, 4]);
+        let counts: Vec<u64> = (1..=5).map(|m| counted.count(m)).collect();
+        assert_eq!(counts, vec![6, 4, 3, 0, 0]);
+        assert_eq!(counted.product(&[2, 3]), Some(12));
+        assert_eq!(counted.product(&[1, 2, 3]), Some(72));
+        assert_eq!(counted.product(&[2, 4]), Some(0));
+        assert_eq!(counted.product(&[]), Some(1));
+        assert_eq!(counted.product(&[5]), None);
+    }
+
+    #[test]
+    fn test_day_02a_multiplicity_options() {
+        let args = |args: &[&str]| day_args(args, &["counts", "product"]);
+        assert_eq!(multiplicity_options(&args(&[])), Ok(None));
+        assert_eq!(
+            multiplicity_options(&args(&["multiplicities"])),
+            Ok(Some(MultiplicityOptions {
+                counts: vec![2, 3],
+                product: vec![2, 3],
+            }))
+        );
+        assert_eq!(
+            multiplicity_options(&args(&[
+                "multiplicities",
+                "--counts",
+                "4,2",
+                "--product=2,5"
+            ])),
+            Ok(Some(MultiplicityOptions {
+                counts: vec![2, 4, 5],
+                product: vec![2, 5],
+            }))
+        );
+        assert!(multiplicity_options(&args(&["--counts", "2"])).is_err());
+        assert!(multiplicity_options(&args(&["multiplicities", "--counts", "0,2"])).is_err());
+        assert!(multiplicity_options(&args(&["multiplicities", "--product", "two"])).is_err());
+        assert!(multiplicity_options(&args(&["multiplicities", "--format", "json"])).is_err());
+    }
+
+    #[test]
+    fn test_day_02b_example_01() {
+        let mut input_handle =
+            Cursor::new("abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz\n\n".as_bytes());
+        let mut box_ids = Vec::with_capacity(256);
+        read_box_ids(&mut input_handle, &mut box_ids, &mut Diagnostics::strict()).unwrap();
+        let box_id = process_box_ids(&box_ids).unwrap();
+
+        assert_eq!(box_ids.len(), 7);
+        assert_eq!(box_id, "fgij");
+    }
+
+    #[test]
+    fn test_day_02_solution() {
+        let mut diagnostics = Diagnostics::strict();
+        let input = "abcdef\nbababc\nabbcde\nabcccd\naabcdd\nabcdee\nababab\n";
+        let box_ids = Day02::parse(input.as_bytes(), &mut diagnostics).unwrap();
+        assert_eq!(Day02::part1(&box_ids).ok(), Some(12));
+
+        let input = "abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz\n";
+        let box_ids = Day02::parse(input.as_bytes(), &mut diagnostics).unwrap();
+        assert_eq!(Day02::part2(&box_ids).ok(), Some("fgij".to_string()));
+    }
+
+    #[test]
+    fn test_day_02_bad_box_id() {
+        let mut diagnostics = Diagnostics::strict();
+        let error = Day02::parse("abcde\nfgH1j\n".as_bytes(), &mut diagnostics).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: box IDs are lowercase letters only ('fgH1j')"
+        );
+    }
+
+    #[test]
+    fn test_day_02_no_common_id() {
+        let box_ids = vec!["abc".to_string(), "xyz".to_string()];
+        assert!(process_box_ids(&box_ids).is_err());
+    }
+
+    #[test]
+    fn test_day_02_line_endings() {
+        // Line endings and trailing spaces must not end up in the IDs
+        for input in &[
+            "abcde\nfghij\nfguij\n",
+            "abcde\r\nfghij\r\nfguij\r\n",
+            "abcde\nfghij\r\nfguij",
+            "\u{feff}abcde \r\nfghij\t\nfguij",
+        ] {
+            let box_ids = Day02::parse(input.as_bytes(), &mut Diagnostics::strict()).unwrap();
+            assert_eq!(box_ids, vec!["abcde", "fghij", "fguij"]);
+            assert_eq!(Day02::part2(&box_ids).ok(), Some("fgij".to_string()));
+        }
+    }
+}
@@ -0,0 +1,363 @@
+//! Day 3: No Matter How You Slice It
+//!
+//! https://adventofcode.com/2018/day/3
+
+use error::{Diagnostics, Error, Result};
+use input;
+use solution::Solution;
+use std::collections::HashMap;
+use std::io::BufRead;
+use trace;
+
+#[derive(Debug, PartialEq)]
+pub struct Claims {
+    pub ids: Vec<String>,
+    pub bounding_boxes: Vec<[i32; 4]>,
+}
+
+/// Column (from 1) of a field split out of the line, counting characters
+/// as day 1 does.
+fn column_of(line: &str, field: &str) -> usize {
+    let offset = field.as_ptr() as usize - line.as_ptr() as usize;
+    line[..offset].chars().count() + 1
+}
+
+fn parse_number(line_number: usize, line: &str, field: &str) -> Result<i32> {
+    field
+        .parse::<i32>()
+        .map_err(|e| Error::parse(line_number, column_of(line, field), line, e))
+}
+
+/// Parse a pair like `1,3` or `4x4` split by `separator`. Either number
+/// below `least` is reported at its column with `too_small`.
+fn parse_pair(
+    line_number: usize,
+    line: &str,
+    field: &str,
+    separator: char,
+    least: i32,
+    too_small: &str,
+) -> Result<(i32, i32)> {
+    let mut parts = field.splitn(2, separator);
+    let number = |part: &str| {
+        let number = parse_number(line_number, line, part)?;
+        if number < least {
+            return Err(Error::parse(
+                line_number,
+                column_of(line, part),
+                line,
+                too_small,
+            ));
+        }
+        Ok(number)
+    };
+
+    // splitn always gives at least one part, even for an empty field
+    let first = number(parts.next().unwrap())?;
+    match parts.next() {
+        Some(second) => Ok((first, number(second)?)),
+        None => Err(Error::parse(
+            line_number,
+            column_of(line, field),
+            line,
+            format!("expected two numbers separated by '{}'", separator),
+        )),
+    }
+}
+
+/// Parse a claim like `#1 @ 1,3: 4x4` into its ID and `[left, top, width, height]`.
+fn parse_claim(line_number: usize, line: &str) -> Result<(String, [i32; 4])> {
+    let mut fields = line.split_whitespace();
+
+    // Running out of fields is reported just past the end of the line
+    let missing = |expected: &str| {
+        Error::parse(
+            line_number,
+            line.chars().count() + 1,
+            line,
+            format!("expected {}", expected),
+        )
+    };
+    let unexpected = |field: &str, message: &str| {
+        Error::parse(line_number, column_of(line, field), line, message)
+    };
+
+    let claim_id = fields.next().ok_or_else(|| missing("a claim ID"))?;
+    if !claim_id.starts_with('#') {
+        return Err(unexpected(claim_id, "claim IDs start with '#'"));
+    }
+
+    let at = fields.next().ok_or_else(|| missing("'@'"))?;
+    if at != "@" {
+        return Err(unexpected(at, "expected '@'"));
+    }
+
+    let raw_pos = fields.next().ok_or_else(|| missing("a position"))?;
+    if !raw_pos.ends_with(':') {
+        return Err(unexpected(raw_pos, "expected ':' after the position"));
+    }
+    let (left, top) = parse_pair(
+        line_number,
+        line,
+        &raw_pos[..raw_pos.len() - 1],
+        ',',
+        0,
+        "positions can't be negative",
+    )?;
+
+    let raw_size = fields.next().ok_or_else(|| missing("a size"))?;
+    let (width, height) = parse_pair(
+        line_number,
+        line,
+        raw_size,
+        'x',
+        1,
+        "sizes must be at least 1",
+    )?;
+
+    // The far edges have to fit as well
+    if left.checked_add(width).is_none() || top.checked_add(height).is_none() {
+        return Err(unexpected(
+            raw_size,
+            "claim reaches past the largest position",
+        ));
+    }
+
+    if let Some(extra) = fields.next() {
+        return Err(unexpected(extra, "unexpected text after the size"));
+    }
+
+    Ok((claim_id.to_string(), [left, top, width, height]))
+}
+
+/// Read one claim per line into `claims`.
+/// Lines that are not claims go to `diagnostics`.
+pub fn read_claims<T: BufRead>(
+    read_handle: &mut T,
+    claims: &mut Claims,
+    diagnostics: &mut Diagnostics,
+) -> Result<()> {
+    for (index, line) in input::lines(read_handle).enumerate() {
+        if let Some((claim_id, bounding_box)) = diagnostics.check(parse_claim(index + 1, &line?))? {
+            trace::trace(format_args!(
+                "  - Claim {} at {},{} is {}x{}",
+                claim_id, bounding_box[0], bounding_box[1], bounding_box[2], bounding_box[3]
+            ));
+            claims.ids.push(claim_id);
+            claims.bounding_boxes.push(bounding_box);
+        }
+    }
+
+    Ok(())
+}
+
+/// How many claims take in each square inch, keyed by (x, y).
+fn claimed_squares(claims: &Claims) -> HashMap<(i32, i32), usize> {
+    let mut squares = HashMap::new();
+
+    for bounding_box in &claims.bounding_boxes {
+        for y in bounding_box[1]..bounding_box[1] + bounding_box[3] {
+            for x in bounding_box[0]..bounding_box[0] + bounding_box[2] {
+                *squares.entry((x, y)).or_insert(0) += 1;
+            }
+        }
+    }
+
+    squares
+}
+
+/// Part 1: the square inches within two or more claims.
+pub fn count_contested_square_inches(claims: &Claims) -> usize {
+    claimed_squares(claims)
+        .values()
+        .filter(|count| **count > 1)
+        .count()
+}
+
+/// Part 2: the one claim that overlaps no other.
+pub fn find_uncontested_claim(claims: &Claims) -> Result<String> {
+    let squares = claimed_squares(claims);
+
+    // The entire claim is contested if one square inch is contested
+    let uncontested_ids: Vec<&String> = claims
+        .bounding_boxes
+        .iter()
+        .zip(&claims.ids)
+        .filter(|(bounding_box, _)| {
+            (bounding_box[1]..bounding_box[1] + bounding_box[3]).all(|y| {
+                (bounding_box[0]..bounding_box[0] + bounding_box[2]).all(|x| squares[&(x, y)] == 1)
+            })
+        })
+        .map(|(_, id)| id)
+        .collect();
+
+    // The puzzle promises just the one
+    if uncontested_ids.len() != 1 {
+        return Err(Error::NoSolution(format!(
+            "expected exactly one uncontested claim, found {}",
+            uncontested_ids.len()
+        )));
+    }
+
+    Ok(uncontested_ids[0].clone())
+}
+
+/// Part 1 counts the square inches claimed more than once, part 2 finds the
+/// one claim that overlaps no other, answered without its `#`.
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+
+    type Input = Claims;
+    type Answer1 = usize;
+    type Answer2 = String;
+
+    fn parse<T: BufRead>(mut handle: T, diagnostics: &mut Diagnostics) -> Result<Claims> {
+        let mut claims = Claims {
+            ids: Vec::default(),
+            bounding_boxes: Vec::default(),
+        };
+
+        read_claims(&mut handle, &mut claims, diagnostics)?;
+        Ok(claims)
+    }
+
+    fn part1(claims: &Claims) -> Result<usize> {
+        Ok(count_contested_square_inches(claims))
+    }
+
+    fn part2(claims: &Claims) -> Result<String> {
+        // The site takes the bare number
+        find_uncontested_claim(claims).map(|claim_id| claim_id.trim_start_matches('#').to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_day_03a_example_01() {
+        let mut input_handle =
+            Cursor::new("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n\n".as_bytes());
+
+        let mut claims = Claims {
+            ids: Vec::default(),
+            bounding_boxes: Vec::default(),
+        };
+
+        read_claims(&mut input_handle, &mut claims, &mut Diagnostics::strict()).unwrap();
+        let contested = count_contested_square_inches(&claims);
+
+        assert_eq!(claims.ids.len(), 3);
+        assert_eq!(claims.bounding_boxes.len(), 3);
+        assert_eq!(contested, 4);
+    }
+
+    #[test]
+    fn test_day_03_every_claim_contested() {
+        // Part 1 has an answer even when part 2 doesn't
+        let input = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n";
+        let claims = Day03::parse(input.as_bytes(), &mut Diagnostics::strict()).unwrap();
+        assert_eq!(Day03::part1(&claims).ok(), Some(4));
+        assert_eq!(
+            Day03::part2(&claims).unwrap_err().to_string(),
+            "No solution: expected exactly one uncontested claim, found 0"
+        );
+    }
+
+    #[test]
+    fn test_day_03_solution() {
+        let input = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n";
+        let claims = Day03::parse(input.as_bytes(), &mut Diagnostics::strict()).unwrap();
+        assert_eq!(Day03::part1(&claims).ok(), Some(4));
+        assert_eq!(Day03::part2(&claims).ok(), Some("3".to_string()));
+    }
+
+    #[test]
+    fn test_day_03_bad_claims() {
+        let errors: Vec<String> = [
+            "1 @ 1,3: 4x4",
+            "#1 at 1,3: 4x4",
+            "#1 @ 1,3 4x4",
+            "#1 @ 1,3: 44",
+            "#1 @ 1,z: 4x4",
+            "#1 @ 1,3:",
+        ]
+        .iter()
+        .map(|line| parse_claim(7, line).unwrap_err().to_string())
+        .collect();
+
+        assert_eq!(
+            errors,
+            vec![
+                "line 7, column 1: claim IDs start with '#' ('1 @ 1,3: 4x4')",
+                "line 7, column 4: expected '@' ('#1 at 1,3: 4x4')",
+                "line 7, column 6: expected ':' after the position ('#1 @ 1,3 4x4')",
+                "line 7, column 11: expected two numbers separated by 'x' ('#1 @ 1,3: 44')",
+                "line 7, column 8: invalid digit found in string ('#1 @ 1,z: 4x4')",
+                "line 7, column 10: expected a size ('#1 @ 1,3:')",
+            ]
+        );
+
+        let errors: Vec<String> = [
+            "#1 @ 1,-3: 4x4",
+            "#1 @ 1,3: 0x4",
+            "#1 @ 1,3: 4x-4",
+            "#1 @ 2147483000,0: 1000x1",
+        ]
+        .iter()
+        .map(|line| parse_claim(7, line).unwrap_err().to_string())
+        .collect();
+
+        assert_eq!(
+            errors,
+            vec![
+                "line 7, column 8: positions can't be negative ('#1 @ 1,-3: 4x4')",
+                "line 7, column 11: sizes must be at least 1 ('#1 @ 1,3: 0x4')",
+                "line 7, column 13: sizes must be at least 1 ('#1 @ 1,3: 4x-4')",
+                "line 7, column 20: claim reaches past the largest position ('#1 @ 2147483000,0: 1000x1')",
+            ]
+        );
+
+        // Right up to the edge is fine
+        assert!(parse_claim(7, "#1 @ 2147483000,0: 647x1").is_ok());
+
+        // Columns count characters, not bytes, as for day 1
+        assert_eq!(
+            parse_claim(7, "#é1 @ 1,z: 4x4").unwrap_err().to_string(),
+            "line 7, column 9: invalid digit found in string ('#é1 @ 1,z: 4x4')"
+        );
+        assert_eq!(
+            parse_claim(7, "#é1 @ 1,3:").unwrap_err().to_string(),
+            "line 7, column 11: expected a size ('#é1 @ 1,3:')"
+        );
+    }
+
+    #[test]
+    fn test_day_03_lenient_skips_bad_claims() {
+        let input = "#1 @ 1,3: 4x4\n#2 @ 3,1 4x4\n#3 @ 5,5: 2x2\n";
+        let mut diagnostics = Diagnostics::lenient();
+        let claims = Day03::parse(input.as_bytes(), &mut diagnostics).unwrap();
+        assert_eq!(claims.ids, vec!["#1", "#3"]);
+        assert_eq!(diagnostics.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_day_03_line_endings() {
+        for input in &[
+            "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n",
+            "#1 @ 1,3: 4x4\r\n#2 @ 3,1: 4x4\r\n#3 @ 5,5: 2x2\r\n",
+            "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\r\n#3 @ 5,5: 2x2",
+            "\u{feff}#1 @ 1,3: 4x4 \r\n#2 @ 3,1: 4x4\t\n#3 @ 5,5: 2x2",
+        ] {
+            let claims = Day03::parse(input.as_bytes(), &mut Diagnostics::strict()).unwrap();
+            assert_eq!(claims.ids, vec!["#1", "#2", "#3"]);
+            assert_eq!(claims.bounding_boxes[2], [5, 5, 2, 2]);
+            assert_eq!(Day03::part1(&claims).ok(), Some(4));
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Errors shared by every parser and solver.
+//!
+//! Bad input is reported through `Diagnostics`, which either hands the first
+//! problem straight back (strict) or notes it and lets parsing carry on past
+//! the bad line (lenient) so everything wrong can be reported at the end.
+
+use std::error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::result;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Reading the input failed.
+    Io(io::Error),
+    /// Writing output to this file failed.
+    Write { path: PathBuf, error: io::Error },
+    /// A line of input could not be understood. Lines and columns count from 1.
+    Parse {
+        line: usize,
+        column: usize,
+        text: String,
+        message: String,
+    },
+    /// Adding up the input went past what the number type can hold, at this
+    /// line (counting from 1).
+    Overflow {
+        line: usize,
+        text: String,
+        message: String,
+    },
+    /// The input was fine but there is no answer to be found in it.
+    NoSolution(String),
+    /// The puzzle site could not be reached or turned the request down.
+    Http(String),
+    /// A new day could not be set up, e.g. as it already exists.
+    Scaffold(String),
+}
+
+pub type Result<T> = result::Result<T, Error>;
+
+impl Error {
+    pub fn parse<M: fmt::Display>(line: usize, column: usize, text: &str, message: M) -> Error {
+        Error::Parse {
+            line,
+            column,
+            text: text.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn write(path: &Path, error: io::Error) -> Error {
+        Error::Write {
+            path: path.to_path_buf(),
+            error,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "Failed to read input: {}", e),
+            Error::Write { path, error } => {
+                write!(f, "Failed to write {}: {}", path.display(), error)
+            }
+            Error::Parse {
+                line,
+                column,
+                text,
+                message,
+            } => write!(
+                f,
+                "line {}, column {}: {} ('{}')",
+                line, column, message, text
+            ),
+            Error::Overflow {
+                line,
+                text,
+                message,
+            } => write!(f, "line {}: {} ('{}')", line, message, text),
+            Error::NoSolution(reason) => write!(f, "No solution: {}", reason),
+            Error::Http(reason) => write!(f, "Request failed: {}", reason),
+            Error::Scaffold(reason) => write!(f, "Setup failed: {}", reason),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+/// Whether the first bad line stops parsing or is just noted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    Strict,
+    Lenient,
+}
+
+/// Collects problems found while parsing, according to the `Mode`.
+#[derive(Debug)]
+pub struct Diagnostics {
+    mode: Mode,
+    errors: Vec<Error>,
+}
+
+impl Diagnostics {
+    pub fn new(mode: Mode) -> Diagnostics {
+        Diagnostics {
+            mode,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Fail on the first problem.
+    pub fn strict() -> Diagnostics {
+        Diagnostics::new(Mode::Strict)
+    }
+
+    /// Skip over problems, keeping them for later.
+    pub fn lenient() -> Diagnostics {
+        Diagnostics::new(Mode::Lenient)
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Hand the error back in strict mode, otherwise keep it and carry on.
+    /// Only bad lines can be carried on past; failing to read at all, or
+    /// finding no answer, always stops.
+    pub fn report(&mut self, error: Error) -> Result<()> {
+        match (self.mode, error) {
+            (Mode::Lenient, error @ Error::Parse { .. }) => {
+                self.errors.push(error);
+                Ok(())
+            }
+            (_, error) => Err(error),
+        }
+    }
+
+    /// Unwrap a parsed value, or report the error and give `None` to skip it.
+    pub fn check<V>(&mut self, parsed: Result<V>) -> Result<Option<V>> {
+        match parsed {
+            Ok(value) => Ok(Some(value)),
+            Err(error) => self.report(error).map(|_| None),
+        }
+    }
+
+    /// Everything reported so far.
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn into_errors(self) -> Vec<Error> {
+        self.errors
+    }
+
+    /// For the binaries: list everything noted on stderr, then give back the
+    /// value, or exit if there was an error after all.
+    pub fn finish<T>(self, result: Result<T>) -> T {
+        for error in &self.errors {
+            eprintln!("ERROR: {}", error);
+        }
+
+        match result {
+            Ok(value) => value,
+            Err(error) => {
+                eprintln!("ERROR: {}", error);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strict_fails_fast() {
+        let mut diagnostics = Diagnostics::strict();
+        let error = diagnostics.report(Error::parse(2, 1, "x", "not a number"));
+        assert!(error.is_err());
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_lenient_collects() {
+        let mut diagnostics = Diagnostics::lenient();
+        let parsed = diagnostics.check::<i32>(Err(Error::parse(2, 1, "x", "not a number")));
+        assert_eq!(parsed.ok(), Some(None));
+        assert_eq!(diagnostics.check(Ok(3)).ok(), Some(Some(3)));
+        assert_eq!(diagnostics.errors().len(), 1);
+        assert_eq!(
+            diagnostics.errors()[0].to_string(),
+            "line 2, column 1: not a number ('x')"
+        );
+    }
+
+    #[test]
+    fn test_lenient_still_stops_on_io() {
+        let mut diagnostics = Diagnostics::lenient();
+        let error = io::Error::other("gone");
+        assert!(diagnostics.report(Error::Io(error)).is_err());
+
+        // Output going wrong says where it was going
+        let error = Error::write(Path::new("counts.csv"), io::Error::other("disk full"));
+        assert_eq!(error.to_string(), "Failed to write counts.csv: disk full");
+        assert!(diagnostics.report(error).is_err());
+    }
+}
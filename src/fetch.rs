@@ -0,0 +1,198 @@
+//! Downloading a day's input into `input/day_XXa/input.txt`.
+//!
+//! Anything already there is used as is and never downloaded again, so
+//! checked in inputs are left alone and the site is only asked once.
+
+use error::{Error, Result};
+use http::{Client, Request};
+use runner;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Where puzzles come from, and who is asking.
+pub struct Site {
+    pub base_url: String,
+    /// The `session` cookie of a logged in browser.
+    pub session: Option<String>,
+}
+
+impl Site {
+    /// `AOC_BASE_URL` and `AOC_SESSION` from the environment, if set.
+    pub fn from_env() -> Site {
+        Site {
+            base_url: env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string()),
+            session: env::var("AOC_SESSION").ok(),
+        }
+    }
+
+    pub fn day_url(&self, day: u8) -> String {
+        format!("{}/2018/day/{}", self.base_url.trim_end_matches('/'), day)
+    }
+
+    /// A request to the site, logged in as `session`.
+    pub fn request(&self, request: Request) -> Result<Request> {
+        match self.session {
+            Some(ref session) => Ok(request.header("Cookie", &format!("session={}", session))),
+            None => Err(Error::Http(
+                "no session token, set AOC_SESSION or pass --session".to_string(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    /// The input was already there.
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Make sure the input for `day` is under `root`, downloading it if not.
+pub fn fetch(day: u8, site: &Site, client: &dyn Client, root: &Path) -> Result<Fetched> {
+    let path = root.join(runner::default_input_path(day));
+    if path.exists() {
+        return Ok(Fetched::Cached(path));
+    }
+
+    let url = format!("{}/input", site.day_url(day));
+    let response = client
+        .send(&site.request(Request::get(&url))?)
+        .map_err(|e| Error::Http(format!("{}: {}", url, e)))?;
+    if !response.is_success() {
+        return Err(Error::Http(format!(
+            "{} gave status {}: {}",
+            url,
+            response.status,
+            response.body.lines().next().unwrap_or("").trim()
+        )));
+    }
+
+    // Written in full before taking its place, so a failed download is
+    // never mistaken for a cached one
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).map_err(|e| Error::write(&path, e))?;
+    }
+    let partial = path.with_extension("txt.part");
+    fs::write(&partial, response.body).map_err(|e| Error::write(&path, e))?;
+    fs::rename(&partial, &path).map_err(|e| Error::write(&path, e))?;
+
+    Ok(Fetched::Downloaded(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::{test_server, Response, TcpClient};
+    use std::io;
+
+    /// Fails the test if anything is requested at all.
+    struct Offline;
+
+    impl Client for Offline {
+        fn send(&self, request: &Request) -> io::Result<Response> {
+            panic!("should not have requested {}", request.url);
+        }
+    }
+
+    fn scratch_directory(name: &str) -> PathBuf {
+        let directory =
+            env::temp_dir().join(format!("advent_2018_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    #[test]
+    fn test_fetch_then_cached() {
+        let root = scratch_directory("fetch");
+        let (url, server) = test_server::serve(vec![(200, "+1\n-2\n")]);
+        let site = Site {
+            base_url: url,
+            session: Some("abc".to_string()),
+        };
+
+        let path = root.join("input/day_07a/input.txt");
+        assert_eq!(
+            fetch(7, &site, &TcpClient::default(), &root).unwrap(),
+            Fetched::Downloaded(path.clone())
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "+1\n-2\n");
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2018/day/7/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=abc\r\n"));
+
+        // The server has gone now, so this has to come from the cache
+        assert_eq!(
+            fetch(7, &site, &Offline, &root).unwrap(),
+            Fetched::Cached(path)
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_refused() {
+        let root = scratch_directory("refused");
+        let (url, server) = test_server::serve(vec![(400, "Puzzle inputs differ by user.\n")]);
+        let site = Site {
+            base_url: url,
+            session: Some("expired".to_string()),
+        };
+
+        let error = fetch(8, &site, &TcpClient::default(), &root).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("status 400: Puzzle inputs differ by user."));
+        assert!(!root.join("input/day_08a/input.txt").exists());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_fetch_unwritable() {
+        // A file where the input directory should go
+        let root = scratch_directory("unwritable");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("input"), "").unwrap();
+        let (url, server) = test_server::serve(vec![(200, "+1\n")]);
+        let site = Site {
+            base_url: url,
+            session: Some("abc".to_string()),
+        };
+
+        let error = fetch(10, &site, &TcpClient::default(), &root).unwrap_err();
+        let path = root.join("input/day_10a/input.txt");
+        assert!(error
+            .to_string()
+            .starts_with(&format!("Failed to write {}: ", path.display())));
+        server.join().unwrap();
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_needs_session() {
+        let root = scratch_directory("session");
+        let site = Site {
+            base_url: "http://localhost".to_string(),
+            session: None,
+        };
+
+        assert!(fetch(9, &site, &Offline, &root).is_err());
+    }
+
+    #[test]
+    fn test_checked_in_input_is_cached() {
+        let site = Site {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session: None,
+        };
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+        assert!(match fetch(1, &site, &Offline, root).unwrap() {
+            Fetched::Cached(_) => true,
+            Fetched::Downloaded(_) => false,
+        });
+    }
+}
@@ -0,0 +1,439 @@
+//! Just enough HTTP to talk to the puzzle site.
+//!
+//! Requests go through the `Client` trait so tests can stand in for the site.
+//! `TcpClient` speaks plain HTTP over a `TcpStream`, which is all a local
+//! stand-in server needs. There is no TLS in std, so `https` URLs are handed
+//! to `curl` by `CurlClient`.
+
+use std::error;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const USER_AGENT: &str = "github.com/paulevans/advent_2018";
+
+pub struct Request {
+    pub method: &'static str,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn get(url: &str) -> Request {
+        Request {
+            method: "GET",
+            url: url.to_string(),
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    /// A form post, with `fields` URL encoded into the body.
+    pub fn post_form(url: &str, fields: &[(&str, &str)]) -> Request {
+        let body = fields
+            .iter()
+            .map(|(name, value)| format!("{}={}", url_encode(name), url_encode(value)))
+            .collect::<Vec<String>>()
+            .join("&");
+
+        Request {
+            method: "POST",
+            url: url.to_string(),
+            headers: vec![(
+                "Content-Type".to_string(),
+                "application/x-www-form-urlencoded".to_string(),
+            )],
+            body,
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Request {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    pub fn is_success(&self) -> bool {
+        self.status >= 200 && self.status < 300
+    }
+}
+
+pub trait Client {
+    fn send(&self, request: &Request) -> io::Result<Response>;
+}
+
+fn url_encode(text: &str) -> String {
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded += &format!("%{:02X}", byte),
+        }
+    }
+    encoded
+}
+
+fn invalid_data<E: Into<Box<dyn error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Plain HTTP/1.1, one connection per request.
+pub struct TcpClient {
+    pub timeout: Duration,
+}
+
+impl Default for TcpClient {
+    fn default() -> TcpClient {
+        TcpClient {
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Client for TcpClient {
+    fn send(&self, request: &Request) -> io::Result<Response> {
+        let rest = request.url.strip_prefix("http://").ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("not an http URL: {}", request.url),
+            )
+        })?;
+        let (host, path) = match rest.find('/') {
+            Some(slash) => (&rest[..slash], &rest[slash..]),
+            None => (rest, "/"),
+        };
+        let address = if host.contains(':') {
+            host.to_string()
+        } else {
+            format!("{}:80", host)
+        };
+
+        let mut stream = TcpStream::connect(address)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        let mut head = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nConnection: close\r\n",
+            request.method, path, host, USER_AGENT
+        );
+        for (name, value) in &request.headers {
+            head += &format!("{}: {}\r\n", name, value);
+        }
+        head += &format!("Content-Length: {}\r\n\r\n", request.body.len());
+
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(request.body.as_bytes())?;
+
+        // The server closes the connection once the response is done
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw)?;
+        parse_response(&raw)
+    }
+}
+
+/// Where `needle` first starts in `haystack`.
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Split a raw response into its status and body.
+/// The body stays as bytes until any chunks are put back together, so a
+/// character split across two chunks comes through whole.
+fn parse_response(raw: &[u8]) -> io::Result<Response> {
+    let (head, body) = match find_bytes(raw, b"\r\n\r\n") {
+        Some(end) => (String::from_utf8_lossy(&raw[..end]), &raw[end + 4..]),
+        None => return Err(invalid_data("response ended before the headers did")),
+    };
+
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or_else(|| invalid_data("response has no status"))?;
+
+    let chunked = lines.any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.starts_with("transfer-encoding:") && line.contains("chunked")
+    });
+
+    let body = if chunked {
+        String::from_utf8_lossy(&decode_chunked(body)?).into_owned()
+    } else {
+        String::from_utf8_lossy(body).into_owned()
+    };
+
+    Ok(Response { status, body })
+}
+
+fn decode_chunked(mut body: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+
+    loop {
+        let line_end =
+            find_bytes(body, b"\r\n").ok_or_else(|| invalid_data("chunk has no size"))?;
+        let size = String::from_utf8_lossy(&body[..line_end]);
+        let size = size.split(';').next().unwrap_or("").trim();
+        let size =
+            usize::from_str_radix(size, 16).map_err(|_| invalid_data("chunk size is not hex"))?;
+        if size == 0 {
+            return Ok(decoded);
+        }
+
+        let chunk = body
+            .get(line_end + 2..line_end + 2 + size)
+            .ok_or_else(|| invalid_data("chunk is cut short"))?;
+        decoded.extend_from_slice(chunk);
+        body = body.get(line_end + 4 + size..).unwrap_or(&[]);
+    }
+}
+
+/// Hands the request to `curl`, for `https` URLs.
+///
+/// Headers and the body hold the session cookie, so they go to curl as a
+/// config file on its standard in. Anything on its command line can be read
+/// by every user on the machine.
+pub struct CurlClient;
+
+/// `text` as a quoted curl config value.
+fn curl_quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for character in text.chars() {
+        match character {
+            '"' => quoted += "\\\"",
+            '\\' => quoted += "\\\\",
+            '\n' => quoted += "\\n",
+            '\r' => quoted += "\\r",
+            '\t' => quoted += "\\t",
+            _ => quoted.push(character),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Everything about `request` that isn't safe on curl's command line.
+fn curl_config(request: &Request) -> String {
+    let mut config = String::new();
+    for (name, value) in &request.headers {
+        config += &format!("header = {}\n", curl_quote(&format!("{}: {}", name, value)));
+    }
+    if request.method != "GET" {
+        config += &format!("data-raw = {}\n", curl_quote(&request.body));
+    }
+    config += &format!("url = {}\n", curl_quote(&request.url));
+    config
+}
+
+impl Client for CurlClient {
+    fn send(&self, request: &Request) -> io::Result<Response> {
+        let mut command = Command::new("curl");
+        command
+            .args(["--silent", "--show-error", "--request", request.method])
+            .args(["--user-agent", USER_AGENT])
+            // The status comes last, on a line of its own
+            .args(["--write-out", "\n%{http_code}"])
+            .args(["--config", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = command.spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(curl_config(request).as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "curl failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let text = String::from_utf8_lossy(&output.stdout);
+        let (body, status) = text.rsplit_once('\n').unwrap_or(("", &text));
+        let status = status
+            .trim()
+            .parse::<u16>()
+            .map_err(|_| invalid_data("curl gave no status"))?;
+
+        Ok(Response {
+            status,
+            body: body.to_string(),
+        })
+    }
+}
+
+/// A client for `url`: plain HTTP directly, anything secure through curl.
+pub fn client_for(url: &str) -> io::Result<Box<dyn Client>> {
+    if url.starts_with("http://") {
+        Ok(Box::new(TcpClient::default()))
+    } else if url.starts_with("https://") {
+        Ok(Box::new(CurlClient))
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("URLs start with http:// or https://, not {}", url),
+        ))
+    }
+}
+
+/// A stand-in for the puzzle site, for tests.
+#[cfg(test)]
+pub mod test_server {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// Answer one connection for each of `responses` (status and body) in
+    /// turn, then hand back the raw requests seen.
+    pub fn serve(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            let mut requests = Vec::new();
+
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+
+                let mut request = String::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    request += &line;
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut content = vec![0; length];
+                reader.read_exact(&mut content).unwrap();
+                request += &String::from_utf8(content).unwrap();
+                requests.push(request);
+
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Whatever\r\nContent-Length: {}\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+
+            requests
+        });
+
+        (url, server)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curl_keeps_secrets_off_the_command_line() {
+        let request = Request::post_form(
+            "https://example.com/2018/day/1/answer",
+            &[("level", "1"), ("answer", "a \"b\"")],
+        )
+        .header("Cookie", "session=abc\\def");
+        assert_eq!(
+            curl_config(&request),
+            "header = \"Content-Type: application/x-www-form-urlencoded\"\n\
+             header = \"Cookie: session=abc\\\\def\"\n\
+             data-raw = \"level=1&answer=a%20%22b%22\"\n\
+             url = \"https://example.com/2018/day/1/answer\"\n"
+        );
+    }
+
+    #[test]
+    fn test_curl_client() {
+        // Plain HTTP works through curl too, if it's installed
+        if Command::new("curl").arg("--version").output().is_err() {
+            return;
+        }
+        let (url, server) = test_server::serve(vec![(200, "right")]);
+        let response = CurlClient
+            .send(
+                &Request::post_form(&format!("{}/answer", url), &[("answer", "1023")])
+                    .header("Cookie", "session=abc"),
+            )
+            .unwrap();
+        assert_eq!(response.body, "right");
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /answer HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=abc\r\n"));
+        assert!(requests[0].ends_with("answer=1023"));
+    }
+
+    #[test]
+    fn test_get_and_post() {
+        let (url, server) = test_server::serve(vec![(200, "hello\n"), (404, "gone")]);
+        let client = TcpClient::default();
+
+        let response = client
+            .send(&Request::get(&format!("{}/a/path", url)).header("Cookie", "session=abc"))
+            .unwrap();
+        assert_eq!(
+            response,
+            Response {
+                status: 200,
+                body: "hello\n".to_string()
+            }
+        );
+
+        let response = client
+            .send(&Request::post_form(
+                &format!("{}/form", url),
+                &[("level", "1"), ("answer", "a b&c")],
+            ))
+            .unwrap();
+        assert!(!response.is_success());
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /a/path HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=abc\r\n"));
+        assert!(requests[1].starts_with("POST /form HTTP/1.1\r\n"));
+        assert!(requests[1].ends_with("\r\n\r\nlevel=1&answer=a%20b%26c"));
+    }
+
+    #[test]
+    fn test_parse_chunked_response() {
+        let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n";
+        assert_eq!(parse_response(raw).unwrap().body, "hello world");
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\n").is_err());
+
+        // A character split between chunks is put back together first
+        let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\ncaf\xc3\r\n2\r\n\xa9!\r\n0\r\n\r\n";
+        assert_eq!(parse_response(raw).unwrap().body, "caf\u{e9}!");
+    }
+
+    #[test]
+    fn test_client_for() {
+        assert!(client_for("ftp://example.com").is_err());
+        assert!(client_for("http://localhost").is_ok());
+    }
+}
@@ -0,0 +1,292 @@
+//! Reading puzzle input a line at a time.
+//!
+//! Every day reads its input the same way: one record per line, and an empty
+//! line (or the end of the stream) ends the input. These iterators do that
+//! once so the day binaries don't have to copy the `read_line` loop around.
+//! Input that has to be read more than once goes through `Replay`.
+
+use error::{Error, Result};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+
+/// Iterator over the lines of a reader, with the line endings removed.
+pub struct Lines<T> {
+    handle: T,
+    // Re-using buffer rather than allocating new string each read.
+    buffer: String,
+    line_number: usize,
+    stop_at_blank: bool,
+    finished: bool,
+}
+
+/// Lines up to the first empty line or the end of the input.
+pub fn lines<T: BufRead>(handle: T) -> Lines<T> {
+    Lines {
+        handle,
+        buffer: String::with_capacity(32),
+        line_number: 0,
+        stop_at_blank: true,
+        finished: false,
+    }
+}
+
+/// Every line up to the end of the input, empty lines included.
+pub fn all_lines<T: BufRead>(handle: T) -> Lines<T> {
+    Lines {
+        stop_at_blank: false,
+        ..lines(handle)
+    }
+}
+
+/// Byte order mark some Windows editors put at the start of a file.
+const BYTE_ORDER_MARK: char = '\u{feff}';
+
+/// Strip the `\n` or `\r\n` that `read_line` leaves on the end, along with
+/// any trailing spaces or tabs. Whichever platform we were built on, either
+/// line ending can turn up, even mixed within the one file.
+fn trim_line_ending(line: &str) -> &str {
+    line.trim_end()
+}
+
+impl<T> Lines<T> {
+    /// Number of the line last read, counting from 1.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+}
+
+impl<T: BufRead> Iterator for Lines<T> {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Result<String>> {
+        if self.finished {
+            return None;
+        }
+
+        self.buffer.clear();
+        match self.handle.read_line(&mut self.buffer) {
+            Ok(0) => {
+                self.finished = true;
+                return None;
+            }
+            Ok(_) => self.line_number += 1,
+            Err(e) => {
+                self.finished = true;
+                return Some(Err(Error::Io(e)));
+            }
+        }
+
+        let mut line = trim_line_ending(&self.buffer);
+        if self.line_number == 1 {
+            line = line.strip_prefix(BYTE_ORDER_MARK).unwrap_or(line);
+        }
+
+        // Empty lines end the input
+        if self.stop_at_blank && line.is_empty() {
+            self.finished = true;
+            return None;
+        }
+
+        Some(Ok(line.to_string()))
+    }
+}
+
+/// Iterator over groups of lines separated by empty lines, see `sections`.
+pub struct Sections<T> {
+    lines: Lines<T>,
+}
+
+/// Split the whole input into sections separated by one or more empty lines.
+pub fn sections<T: BufRead>(handle: T) -> Sections<T> {
+    Sections {
+        lines: all_lines(handle),
+    }
+}
+
+impl<T: BufRead> Iterator for Sections<T> {
+    type Item = Result<Vec<String>>;
+
+    fn next(&mut self) -> Option<Result<Vec<String>>> {
+        let mut section = Vec::new();
+
+        for line in &mut self.lines {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if !line.is_empty() {
+                section.push(line);
+            } else if !section.is_empty() {
+                break;
+            }
+        }
+
+        if section.is_empty() {
+            None
+        } else {
+            Some(Ok(section))
+        }
+    }
+}
+
+/// Input that can go back to the start and be read again.
+pub trait Replay: BufRead {
+    fn replay(&mut self) -> Result<()>;
+}
+
+impl<T: AsRef<[u8]>> Replay for Cursor<T> {
+    fn replay(&mut self) -> Result<()> {
+        self.set_position(0);
+        Ok(())
+    }
+}
+
+impl Replay for BufReader<File> {
+    fn replay(&mut self) -> Result<()> {
+        self.seek(SeekFrom::Start(0)).map_err(Error::Io)?;
+        Ok(())
+    }
+}
+
+impl<R: Replay + ?Sized> Replay for Box<R> {
+    fn replay(&mut self) -> Result<()> {
+        (**self).replay()
+    }
+}
+
+/// Keeps everything read from a reader that can't seek, such as standard
+/// in, so it can be read again from memory. See `replayable`.
+pub struct Replayable<R> {
+    source: R,
+    /// Everything read from `source` so far.
+    recorded: Vec<u8>,
+    /// How far into `recorded` reading has got. At the end, more is read
+    /// from `source`.
+    position: usize,
+}
+
+/// Make `source` replayable by keeping what's read from it.
+pub fn replayable<R: BufRead>(source: R) -> Replayable<R> {
+    Replayable {
+        source,
+        recorded: Vec::new(),
+        position: 0,
+    }
+}
+
+impl<R: BufRead> Read for Replayable<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let read = self.fill_buf()?.read(buffer)?;
+        self.consume(read);
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> BufRead for Replayable<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.position == self.recorded.len() {
+            let available = self.source.fill_buf()?;
+            let length = available.len();
+            self.recorded.extend_from_slice(available);
+            self.source.consume(length);
+        }
+        Ok(&self.recorded[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position = (self.position + amount).min(self.recorded.len());
+    }
+}
+
+impl<R: BufRead> Replay for Replayable<R> {
+    fn replay(&mut self) -> Result<()> {
+        self.position = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_lines_stop_at_blank() {
+        let input = Cursor::new("a\nb\n\nc\n".as_bytes());
+        let read: Vec<String> = lines(input).map(Result::unwrap).collect();
+        assert_eq!(read, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_lines_without_trailing_newline() {
+        let input = Cursor::new("a\r\nb".as_bytes());
+        let read: Vec<String> = lines(input).map(Result::unwrap).collect();
+        assert_eq!(read, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_lines_mixed_line_endings() {
+        let input = Cursor::new("a\r\nb\nc\r\n".as_bytes());
+        let read: Vec<String> = lines(input).map(Result::unwrap).collect();
+        assert_eq!(read, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_lines_trailing_whitespace() {
+        let input = Cursor::new("a \t\nb\r\n  \r\nc\n".as_bytes());
+        let read: Vec<String> = lines(input).map(Result::unwrap).collect();
+        assert_eq!(read, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_lines_byte_order_mark() {
+        let input = Cursor::new("\u{feff}a\r\n\u{feff}b\r\n".as_bytes());
+        let read: Vec<String> = lines(input).map(Result::unwrap).collect();
+
+        // Only the start of the file can have one
+        assert_eq!(read, vec!["a", "\u{feff}b"]);
+    }
+
+    #[test]
+    fn test_lines_leave_reader_after_blank() {
+        let mut input = Cursor::new("1\n\n2\n".as_bytes());
+        assert_eq!(lines(&mut input).count(), 1);
+        assert_eq!(lines(&mut input).next().unwrap().unwrap(), "2");
+    }
+
+    #[test]
+    fn test_replayable() {
+        // A plain slice can't seek
+        let mut input = replayable("a\nb\n\nc\n".as_bytes());
+        assert_eq!(lines(&mut input).count(), 2);
+        input.replay().unwrap();
+
+        // Again from memory, then on into what wasn't read the first time
+        let read: Vec<String> = all_lines(&mut input).map(Result::unwrap).collect();
+        assert_eq!(read, vec!["a", "b", "", "c"]);
+        input.replay().unwrap();
+        let read: Vec<String> = lines(&mut input).map(Result::unwrap).collect();
+        assert_eq!(read, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut input: Box<dyn Replay> = Box::new(Cursor::new("a\nb\n".as_bytes()));
+        assert_eq!(lines(&mut input).count(), 2);
+        input.replay().unwrap();
+        assert_eq!(lines(&mut input).next().unwrap().unwrap(), "a");
+
+        let mut input = BufReader::new(File::open("input/day_01a/input.txt").unwrap());
+        let first = lines(&mut input).next().unwrap().unwrap();
+        input.replay().unwrap();
+        assert_eq!(lines(&mut input).next().unwrap().unwrap(), first);
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = Cursor::new("a\nb\n\n\nc\n\n".as_bytes());
+        let read: Vec<Vec<String>> = sections(input).map(Result::unwrap).collect();
+        assert_eq!(read, vec![vec!["a", "b"], vec!["c"]]);
+    }
+}
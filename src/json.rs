@@ -0,0 +1,410 @@
+//! Just enough JSON to write results out and read saved baselines back in.
+//!
+//! Objects keep their keys in the order they were built so output is stable
+//! from run to run.
+
+use error::{Error, Result};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Look a key up in an object. Anything else has no keys.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Json {
+    fn from(string: &'a str) -> Json {
+        Json::String(string.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(string: String) -> Json {
+        Json::String(string)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(number: f64) -> Json {
+        Json::Number(number)
+    }
+}
+
+impl From<u64> for Json {
+    fn from(number: u64) -> Json {
+        Json::Number(number as f64)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(number: usize) -> Json {
+        Json::Number(number as f64)
+    }
+}
+
+impl From<u8> for Json {
+    fn from(number: u8) -> Json {
+        Json::Number(f64::from(number))
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Json {
+        Json::Bool(value)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Json {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(values: Vec<T>) -> Json {
+        Json::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, string: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in string.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+/// Compact JSON on a single line.
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            // Whole numbers print without a trailing `.0`
+            Json::Number(number) if number.is_finite() => write!(f, "{}", number),
+            Json::Number(_) => write!(f, "null"),
+            Json::String(string) => write_string(f, string),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(members) => {
+                write!(f, "{{")?;
+                for (index, (name, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, name)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// Parse a complete JSON document.
+pub fn parse(text: &str) -> Result<Json> {
+    let mut parser = Parser { text, position: 0 };
+    let value = parser.value()?;
+
+    parser.skip_whitespace();
+    if parser.position < text.len() {
+        return Err(parser.error("unexpected text after the value"));
+    }
+
+    Ok(value)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Errors point at the line and column the parser had got to.
+    fn error(&self, message: &str) -> Error {
+        let before = &self.text[..self.position];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        let line_end = self.text[line_start..]
+            .find('\n')
+            .map_or(self.text.len(), |index| line_start + index);
+
+        Error::parse(
+            line,
+            before[line_start..].chars().count() + 1,
+            &self.text[line_start..line_end],
+            message,
+        )
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.position += c.len_utf8();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.position += 1;
+                Ok(())
+            }
+            _ => Err(self.error(&format!("expected '{}'", expected))),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Json) -> Result<Json> {
+        if self.text[self.position..].starts_with(keyword) {
+            self.position += keyword.len();
+            Ok(value)
+        } else {
+            Err(self.error("expected a value"))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('n') => self.keyword("null", Json::Null),
+            Some('t') => self.keyword("true", Json::Bool(true)),
+            Some('f') => self.keyword("false", Json::Bool(false)),
+            Some('"') => self.string().map(Json::String),
+            Some('[') => self.array(),
+            Some('{') => self.object(),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    fn number(&mut self) -> Result<Json> {
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if !(c.is_ascii_digit() || "+-.eE".contains(c)) {
+                break;
+            }
+            self.position += 1;
+        }
+
+        match self.text[start..self.position].parse::<f64>() {
+            Ok(number) => Ok(Json::Number(number)),
+            Err(_) => {
+                self.position = start;
+                Err(self.error("expected a number"))
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.expect('"')?;
+        let mut string = String::new();
+
+        loop {
+            match self.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => {
+                    let escaped = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.unicode_escape()?,
+                        _ => return Err(self.error("unknown escape")),
+                    };
+                    string.push(escaped);
+                }
+                Some(c) => string.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn unicode_escape(&mut self) -> Result<char> {
+        let hex = self.text.get(self.position..self.position + 4);
+        let code = hex.and_then(|hex| u32::from_str_radix(hex, 16).ok());
+
+        // Surrogate pairs are not needed for anything we write
+        match code.and_then(::std::char::from_u32) {
+            Some(c) => {
+                self.position += 4;
+                Ok(c)
+            }
+            None => Err(self.error("expected four hex digits")),
+        }
+    }
+
+    fn array(&mut self) -> Result<Json> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(Json::Array(values));
+        }
+
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Json::Array(values)),
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(Json::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let name = self.string()?;
+            self.expect(':')?;
+            members.push((name, self.value()?));
+
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Json::Object(members)),
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write() {
+        let value = Json::Object(vec![
+            ("day".to_string(), Json::from(1u8)),
+            ("answer".to_string(), Json::from("a \"quoted\"\nline")),
+            ("time".to_string(), Json::from(1.5)),
+            ("missing".to_string(), Json::from(None::<String>)),
+            ("list".to_string(), Json::from(vec![true, false])),
+        ]);
+
+        assert_eq!(
+            value.to_string(),
+            r#"{"day":1,"answer":"a \"quoted\"\nline","time":1.5,"missing":null,"list":[true,false]}"#
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = r#"{"name":"tab\there","values":[1,-2.5,1e3,null],"nested":{"ok":true}}"#;
+        let value = parse(text).unwrap();
+
+        assert_eq!(value.get("name").and_then(Json::as_str), Some("tab\there"));
+        assert_eq!(
+            value
+                .get("values")
+                .and_then(Json::as_array)
+                .map(|values| values.len()),
+            Some(4)
+        );
+        assert_eq!(parse(&value.to_string()).unwrap(), value);
+    }
+
+    #[test]
+    fn test_parse_whitespace_and_escapes() {
+        let value = parse(" [ \"\\u0041\" ,\n {} ] ").unwrap();
+        assert_eq!(
+            value,
+            Json::Array(vec![Json::from("A"), Json::Object(Vec::new())])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse("{\n  \"a\": 1,\n  \"b\" 2\n}")
+                .unwrap_err()
+                .to_string(),
+            "line 3, column 7: expected ':' ('  \"b\" 2')"
+        );
+        assert!(parse("[1, 2").is_err());
+        assert!(parse("1 2").is_err());
+    }
+}
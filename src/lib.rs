@@ -0,0 +1,21 @@
+//! Shared code for the Advent of Code 2018 day binaries.
+
+pub mod bench;
+pub mod bigint;
+pub mod cli;
+pub mod day_01;
+pub mod day_02;
+pub mod day_03;
+pub mod error;
+pub mod fetch;
+pub mod http;
+pub mod input;
+pub mod json;
+pub mod readme;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod submit;
+pub mod trace;
+pub mod verify;
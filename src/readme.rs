@@ -0,0 +1,220 @@
+//! Keeping the README in step with the registered puzzles, for `aoc readme`.
+//!
+//! Generated parts of the README sit between `<!-- generated: NAME -->` and
+//! `<!-- end generated: NAME -->`. The `contents` list and the `days`
+//! sections are rebuilt from the runner's registry every time. `timings`
+//! change from run to run, so they are only rebuilt when asked and are
+//! otherwise left as they are.
+
+use bench::Measurement;
+use runner::{self, PUZZLES};
+use std::path::Path;
+
+/// Each registered day, with its parts, in order.
+pub fn registered_days() -> Vec<(u8, Vec<char>)> {
+    let mut days: Vec<(u8, Vec<char>)> = Vec::new();
+
+    for puzzle in PUZZLES {
+        match days.iter_mut().find(|(day, _)| *day == puzzle.day) {
+            Some((_, parts)) => parts.push(puzzle.part),
+            None => days.push((puzzle.day, vec![puzzle.part])),
+        }
+    }
+
+    days.sort();
+    days
+}
+
+/// `a` is exercise 1, `b` exercise 2.
+fn exercise(part: char) -> u32 {
+    part as u32 - 'a' as u32 + 1
+}
+
+pub fn contents(days: &[(u8, Vec<char>)]) -> String {
+    let mut contents = "* [Running Solutions](#Running)\n".to_string();
+
+    for (day, parts) in days {
+        contents += &format!("* Day {:02}\n", day);
+        for part in parts {
+            contents += &format!(
+                "  * [ Exercise {}](#Day{:02}{})\n",
+                exercise(*part),
+                day,
+                part
+            );
+        }
+    }
+
+    contents
+}
+
+/// A section per day, with how to test and run each part. Days that have
+/// a binary of their own under `root` show it as well as `aoc run`.
+pub fn day_sections(days: &[(u8, Vec<char>)], root: &Path) -> String {
+    let mut sections = Vec::new();
+
+    for (day, parts) in days {
+        let input = runner::default_input_path(*day);
+        let mut section = format!(
+            "## Day {day:02}\nhttps://adventofcode.com/2018/day/{day}\n\n\
+             Input is `{input}`, with the known answers in `{answers}`.\n",
+            day = day,
+            input = input.display(),
+            answers = runner::default_answers_path(*day).display()
+        );
+
+        for part in parts {
+            let binary = format!("day_{:02}{}", day, part);
+            let mut run = String::new();
+            if root.join(format!("src/bin/{}.rs", binary)).exists() {
+                run += &format!("cargo run --bin {} -- < {}\n", binary, input.display());
+            }
+            run += &format!("cargo run --bin aoc -- run {}{}\n", day, part);
+
+            section += &format!(
+                "
+<a name=\"Day{day:02}{part}\"></a>
+### Day {day:02} Exercise {exercise}
+
+#### Testing
+```
+cargo test day_{day:02}::
+```
+
+#### Run On Example Input
+```
+{run}```
+",
+                day = day,
+                part = part,
+                exercise = exercise(*part),
+                run = run
+            );
+        }
+
+        sections.push(section);
+    }
+
+    sections.join("\n")
+}
+
+/// The median of each stage, one day per row.
+pub fn timings_table(measurements: &[Measurement]) -> String {
+    let mut table =
+        "| Day | Parse | Part 1 | Part 2 |\n|-----|-------|--------|--------|\n".to_string();
+    let mut days: Vec<u8> = measurements.iter().map(|m| m.day).collect();
+    days.dedup();
+
+    for day in days {
+        let median = |stage: &str| {
+            measurements
+                .iter()
+                .find(|m| m.day == day && m.stage == stage)
+                .map_or("-".to_string(), |m| format!("{:.1?}", m.median))
+        };
+        table += &format!(
+            "| {:02} | {} | {} | {} |\n",
+            day,
+            median("parse"),
+            median("a"),
+            median("b")
+        );
+    }
+
+    table
+}
+
+/// Swap what is between the `name` markers for `content`.
+pub fn replace_block(text: &str, name: &str, content: &str) -> Result<String, String> {
+    let begin = format!("<!-- generated: {} -->\n", name);
+    let end = format!("<!-- end generated: {} -->", name);
+
+    let start = text
+        .find(&begin)
+        .ok_or_else(|| format!("README has no '{}' marker", begin.trim()))?
+        + begin.len();
+    let finish = text[start..]
+        .find(&end)
+        .ok_or_else(|| format!("README has no '{}' marker", end))?
+        + start;
+
+    Ok(format!("{}{}{}", &text[..start], content, &text[finish..]))
+}
+
+/// The README with its contents and day sections rebuilt for `days`.
+pub fn generate(text: &str, days: &[(u8, Vec<char>)], root: &Path) -> Result<String, String> {
+    let text = replace_block(text, "contents", &contents(days))?;
+    replace_block(&text, "days", &day_sections(days, root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Duration;
+
+    #[test]
+    fn test_readme_is_up_to_date() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let readme = fs::read_to_string(root.join("README.md")).unwrap();
+        let generated = generate(&readme, &registered_days(), root).unwrap();
+
+        assert!(
+            generated == readme,
+            "README.md is out of date, run `cargo run --bin aoc -- readme`"
+        );
+    }
+
+    #[test]
+    fn test_registered_days() {
+        let days = registered_days();
+        assert_eq!(days[0], (1, vec!['a', 'b']));
+        assert_eq!(days.len() * 2, PUZZLES.len());
+    }
+
+    #[test]
+    fn test_day_sections() {
+        let sections = day_sections(
+            &[(3, vec!['a', 'b'])],
+            Path::new(env!("CARGO_MANIFEST_DIR")),
+        );
+
+        assert!(sections.starts_with(
+            "## Day 03\nhttps://adventofcode.com/2018/day/3\n\n\
+             Input is `input/day_03a/input.txt`, with the known answers in `input/day_03a/answers.txt`.\n"
+        ));
+        assert!(sections.contains(
+            "```\ncargo run --bin day_03a -- < input/day_03a/input.txt\ncargo run --bin aoc -- run 3a\n```\n"
+        ));
+        // There is no day_03b binary, day_03a answers both parts
+        assert!(sections.contains("### Day 03 Exercise 2\n"));
+        assert!(sections.contains("```\ncargo run --bin aoc -- run 3b\n```\n"));
+    }
+
+    #[test]
+    fn test_replace_block() {
+        let text = "top\n<!-- generated: list -->\nold\n<!-- end generated: list -->\nbottom\n";
+        assert_eq!(
+            replace_block(text, "list", "new\n").unwrap(),
+            "top\n<!-- generated: list -->\nnew\n<!-- end generated: list -->\nbottom\n"
+        );
+        assert!(replace_block(text, "other", "new\n").is_err());
+    }
+
+    #[test]
+    fn test_timings_table() {
+        let measurement = |stage: &str, median_ms: u64| Measurement {
+            day: 2,
+            stage: stage.to_string(),
+            min: Duration::from_millis(median_ms),
+            median: Duration::from_millis(median_ms),
+            max: Duration::from_millis(median_ms),
+            allocations: 0,
+        };
+
+        assert!(
+            timings_table(&[measurement("parse", 1), measurement("a", 20)])
+                .ends_with("| 02 | 1.0ms | 20.0ms | - |\n")
+        );
+    }
+}
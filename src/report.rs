@@ -0,0 +1,265 @@
+//! Results in a form other programs can read.
+//!
+//! `--format json` prints one document per run:
+//!
+//! ```text
+//! {"results": [{"day": 1, "part": "a", "answer": "402",
+//!               "timings": {"parse_ns": 81234, "solve_ns": 912},
+//!               "diagnostics": [], "error": null}]}
+//! ```
+//!
+//! `answer` is always a string, or `null` along with `timings` when `error`
+//! says why there is no answer. Each diagnostic and error has a `kind` of
+//! `parse`, `overflow`, `io`, `write`, `no_solution`, `http` or `scaffold`
+//! and a `message`.
+//! Parse problems also give the `line`, `column` and `text` they were found
+//! at, and overflows the `line` and `text`.
+
+use error::{Diagnostics, Error, Mode, Result};
+use input::Replay;
+use json::Json;
+use runner::{self, Puzzle};
+use solution::Solved;
+use std::convert::TryFrom;
+use std::io::{BufRead, Cursor};
+use std::path::Path;
+use std::process;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How results are printed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> ::std::result::Result<Format, String> {
+        match format {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown format '{}', try text or json", format)),
+        }
+    }
+}
+
+/// Everything that came of solving one puzzle.
+pub struct Report {
+    pub puzzle: &'static Puzzle,
+    pub result: Result<Solved>,
+    /// Problems skipped over in lenient mode.
+    pub diagnostics: Vec<Error>,
+}
+
+impl Report {
+    pub fn solve(puzzle: &'static Puzzle, handle: &mut dyn Replay, mode: Mode) -> Report {
+        let mut diagnostics = Diagnostics::new(mode);
+        let result = (puzzle.solve)(handle, &mut diagnostics);
+        Report {
+            puzzle,
+            result,
+            diagnostics: diagnostics.into_errors(),
+        }
+    }
+
+    pub fn solve_file(puzzle: &'static Puzzle, path: &Path, mode: Mode) -> Report {
+        let mut diagnostics = Diagnostics::new(mode);
+        let result = puzzle.solve_file(path, &mut diagnostics);
+        Report {
+            puzzle,
+            result,
+            diagnostics: diagnostics.into_errors(),
+        }
+    }
+
+    pub fn to_json(&self) -> Json {
+        let (answer, timings, error) = match self.result {
+            Ok(ref solved) => (
+                Json::from(solved.answer.as_str()),
+                Json::Object(vec![
+                    (
+                        "parse_ns".to_string(),
+                        Json::from(nanoseconds(solved.parse_time)),
+                    ),
+                    (
+                        "solve_ns".to_string(),
+                        Json::from(nanoseconds(solved.solve_time)),
+                    ),
+                ]),
+                Json::Null,
+            ),
+            Err(ref error) => (Json::Null, Json::Null, error_to_json(error)),
+        };
+
+        Json::Object(vec![
+            ("day".to_string(), Json::from(self.puzzle.day)),
+            ("part".to_string(), Json::from(self.puzzle.part.to_string())),
+            ("answer".to_string(), answer),
+            ("timings".to_string(), timings),
+            (
+                "diagnostics".to_string(),
+                Json::Array(self.diagnostics.iter().map(error_to_json).collect()),
+            ),
+            ("error".to_string(), error),
+        ])
+    }
+}
+
+/// Timings are written as whole nanoseconds, for here and the benchmarks.
+/// Anything past `u64::MAX` (over 500 years) is written as that.
+pub fn nanoseconds(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+fn error_to_json(error: &Error) -> Json {
+    let message = |kind: &str, message: String| {
+        Json::Object(vec![
+            ("kind".to_string(), Json::from(kind)),
+            ("message".to_string(), Json::from(message)),
+        ])
+    };
+
+    match error {
+        Error::Io(e) => message("io", e.to_string()),
+        Error::Write { .. } => message("write", error.to_string()),
+        Error::NoSolution(reason) => message("no_solution", reason.clone()),
+        Error::Http(reason) => message("http", reason.clone()),
+        Error::Scaffold(reason) => message("scaffold", reason.clone()),
+        Error::Parse {
+            line,
+            column,
+            text,
+            message,
+        } => Json::Object(vec![
+            ("kind".to_string(), Json::from("parse")),
+            ("message".to_string(), Json::from(message.as_str())),
+            ("line".to_string(), Json::from(*line)),
+            ("column".to_string(), Json::from(*column)),
+            ("text".to_string(), Json::from(text.as_str())),
+        ]),
+        Error::Overflow {
+            line,
+            text,
+            message,
+        } => Json::Object(vec![
+            ("kind".to_string(), Json::from("overflow")),
+            ("message".to_string(), Json::from(message.as_str())),
+            ("line".to_string(), Json::from(*line)),
+            ("text".to_string(), Json::from(text.as_str())),
+        ]),
+    }
+}
+
+/// The whole document: `{"results": [report, ...]}`.
+pub fn to_json(reports: &[Report]) -> Json {
+    Json::Object(vec![(
+        "results".to_string(),
+        Json::Array(reports.iter().map(Report::to_json).collect()),
+    )])
+}
+
+/// Solve each puzzle over the same input, read once from `handle`.
+pub fn solve_all<T: BufRead>(
+    puzzles: &[&'static Puzzle],
+    mut handle: T,
+    mode: Mode,
+) -> Result<Vec<Report>> {
+    let mut input = Vec::new();
+    handle.read_to_end(&mut input)?;
+
+    Ok(puzzles
+        .iter()
+        .map(|puzzle| Report::solve(puzzle, &mut Cursor::new(&input[..]), mode))
+        .collect())
+}
+
+/// For the day binaries: solve the `selector` puzzles over `handle`, skipping
+/// bad lines, and print the results as JSON. Exits if any puzzle failed.
+pub fn print_json<T: BufRead>(selector: &str, handle: T) {
+    let puzzles = runner::select(selector).expect("day binaries select known puzzles");
+
+    match solve_all(&puzzles, handle, Mode::Lenient) {
+        Ok(reports) => {
+            println!("{}", to_json(&reports));
+            if reports.iter().any(|report| report.result.is_err()) {
+                process::exit(1);
+            }
+        }
+        Err(error) => {
+            eprintln!("ERROR: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use json;
+
+    #[test]
+    fn test_format() {
+        assert_eq!("json".parse::<Format>(), Ok(Format::Json));
+        assert_eq!("text".parse::<Format>(), Ok(Format::Text));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn test_nanoseconds() {
+        assert_eq!(nanoseconds(Duration::new(2, 5)), 2_000_000_005);
+        assert_eq!(nanoseconds(Duration::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_solve_all_to_json() {
+        let puzzles = runner::select("1").unwrap();
+        let reports = solve_all(
+            &puzzles,
+            "+3\nthree\n+3\n+4\n-2\n-4\n".as_bytes(),
+            Mode::Lenient,
+        )
+        .unwrap();
+        let value = json::parse(&to_json(&reports).to_string()).unwrap();
+        let results = value.get("results").and_then(Json::as_array).unwrap();
+        assert_eq!(results.len(), 2);
+
+        let part_b = &results[1];
+        assert_eq!(part_b.get("day").and_then(Json::as_f64), Some(1.0));
+        assert_eq!(part_b.get("part").and_then(Json::as_str), Some("b"));
+        assert_eq!(part_b.get("answer").and_then(Json::as_str), Some("10"));
+        assert!(part_b
+            .get("timings")
+            .and_then(|timings| timings.get("solve_ns"))
+            .and_then(Json::as_f64)
+            .is_some());
+        assert_eq!(part_b.get("error"), Some(&Json::Null));
+
+        let diagnostics = part_b.get("diagnostics").and_then(Json::as_array).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].get("kind").and_then(Json::as_str),
+            Some("parse")
+        );
+        assert_eq!(diagnostics[0].get("line").and_then(Json::as_f64), Some(2.0));
+        assert_eq!(
+            diagnostics[0].get("text").and_then(Json::as_str),
+            Some("three")
+        );
+    }
+
+    #[test]
+    fn test_error_to_json() {
+        let puzzles = runner::select("1b").unwrap();
+        let reports = solve_all(&puzzles, "+1\nthree\n".as_bytes(), Mode::Strict).unwrap();
+
+        assert_eq!(
+            reports[0].to_json().to_string(),
+            "{\"day\":1,\"part\":\"b\",\"answer\":null,\"timings\":null,\"diagnostics\":[],\
+             \"error\":{\"kind\":\"parse\",\"message\":\"invalid digit found in string\",\
+             \"line\":2,\"column\":1,\"text\":\"three\"}}"
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Running puzzles by day and part, for the `aoc` binary.
+
+use day_01::Day01;
+use day_02::Day02;
+use day_03::Day03;
+use error::{Diagnostics, Result};
+use input::Replay;
+use solution::{solve_part1, solve_part2, Solution, Solved};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// One part of one day's puzzle.
+pub struct Puzzle {
+    pub day: u8,
+    pub part: char,
+    pub solve: fn(&mut dyn Replay, &mut Diagnostics) -> Result<Solved>,
+}
+
+/// Every puzzle the runner knows about, in order.
+pub const PUZZLES: &[Puzzle] = &[
+    Puzzle {
+        day: Day01::DAY,
+        part: 'a',
+        solve: solve_part1::<Day01>,
+    },
+    Puzzle {
+        day: Day01::DAY,
+        part: 'b',
+        solve: solve_part2::<Day01>,
+    },
+    Puzzle {
+        day: Day02::DAY,
+        part: 'a',
+        solve: solve_part1::<Day02>,
+    },
+    Puzzle {
+        day: Day02::DAY,
+        part: 'b',
+        solve: solve_part2::<Day02>,
+    },
+    Puzzle {
+        day: Day03::DAY,
+        part: 'a',
+        solve: solve_part1::<Day03>,
+    },
+    Puzzle {
+        day: Day03::DAY,
+        part: 'b',
+        solve: solve_part2::<Day03>,
+    },
+];
+
+impl Puzzle {
+    /// Short name used on the command line, e.g. `1b`.
+    pub fn name(&self) -> String {
+        format!("{}{}", self.day, self.part)
+    }
+
+    /// Where the checked in input for this day lives.
+    pub fn default_input_path(&self) -> PathBuf {
+        default_input_path(self.day)
+    }
+
+    /// Where the known correct answers for the checked in input live.
+    pub fn default_answers_path(&self) -> PathBuf {
+        default_answers_path(self.day)
+    }
+
+    /// Solve this puzzle reading the input from a file.
+    pub fn solve_file(&self, path: &Path, diagnostics: &mut Diagnostics) -> Result<Solved> {
+        let mut handle = BufReader::new(File::open(path)?);
+        (self.solve)(&mut handle, diagnostics)
+    }
+}
+
+/// Inputs are shared by both parts and live under the part a directory.
+pub fn default_input_path(day: u8) -> PathBuf {
+    PathBuf::from(format!("input/day_{:02}a/input.txt", day))
+}
+
+/// Answers sit next to the input they are for.
+pub fn default_answers_path(day: u8) -> PathBuf {
+    PathBuf::from(format!("input/day_{:02}a/answers.txt", day))
+}
+
+/// Pick puzzles with `all`, a day (`1`, `01`) or a day and part (`1b`).
+pub fn select(selector: &str) -> ::std::result::Result<Vec<&'static Puzzle>, String> {
+    if selector == "all" {
+        return Ok(PUZZLES.iter().collect());
+    }
+
+    let (day, part) = match selector.chars().last() {
+        Some(part) if part.is_ascii_alphabetic() => (
+            &selector[..selector.len() - 1],
+            Some(part.to_ascii_lowercase()),
+        ),
+        _ => (selector, None),
+    };
+
+    let day = day
+        .parse::<u8>()
+        .map_err(|_| format!("'{}' is not a day, try 1, 1a or all", selector))?;
+
+    let selected: Vec<&Puzzle> = PUZZLES
+        .iter()
+        .filter(|puzzle| puzzle.day == day && part.is_none_or(|part| puzzle.part == part))
+        .collect();
+
+    if selected.is_empty() {
+        Err(format!("No solution for '{}'", selector))
+    } else {
+        Ok(selected)
+    }
+}
+
+/// Lay answers out one per row under a header.
+pub fn format_table(answers: &[(&Puzzle, String)]) -> String {
+    let mut table = format!("{:<5}{:<6}{}\n", "Day", "Part", "Answer");
+
+    for (puzzle, answer) in answers {
+        table += &format!(
+            "{:<5}{:<6}{}\n",
+            format!("{:02}", puzzle.day),
+            puzzle.part,
+            answer
+        );
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_select_all() {
+        assert_eq!(select("all").unwrap().len(), PUZZLES.len());
+    }
+
+    #[test]
+    fn test_select_day_and_part() {
+        let selected = select("01B").unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].name(), "1b");
+
+        let selected = select("3").unwrap();
+        let names: Vec<String> = selected.iter().map(|puzzle| puzzle.name()).collect();
+        assert_eq!(names, vec!["3a", "3b"]);
+    }
+
+    #[test]
+    fn test_select_unknown() {
+        assert!(select("26a").is_err());
+        assert!(select("first").is_err());
+    }
+
+    #[test]
+    fn test_default_input_path() {
+        assert_eq!(
+            select("2b").unwrap()[0].default_input_path(),
+            PathBuf::from("input/day_02a/input.txt")
+        );
+    }
+
+    #[test]
+    fn test_solve_and_table() {
+        let puzzle = select("1b").unwrap()[0];
+        let mut input = Cursor::new("3\n3\n4\n-2\n-4\n\n".as_bytes());
+        let answer = (puzzle.solve)(&mut input, &mut Diagnostics::strict())
+            .unwrap()
+            .answer;
+        assert_eq!(answer, "10");
+
+        let table = format_table(&[(puzzle, answer)]);
+        assert_eq!(table, "Day  Part  Answer\n01   b     10\n");
+    }
+}
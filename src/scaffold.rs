@@ -0,0 +1,341 @@
+//! Setting up a new day, for `aoc new`.
+//!
+//! Writes the day's module implementing `Solution` (with example tests to
+//! fill in), a binary for each part, the input directory with an empty
+//! answers file, and the README section. The module is registered in
+//! `lib.rs`, the runner and the benchmarks.
+
+use error::{Error, Result};
+use readme;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn module_template(day: u8) -> String {
+    format!(
+        r#"//! Day {day}
+//!
+//! https://adventofcode.com/2018/day/{day}
+
+use error::{{Diagnostics, Error, Result}};
+use input;
+use solution::Solution;
+use std::io::BufRead;
+
+/// Read each line of `read_handle` into `lines`.
+pub fn read_lines<T: BufRead>(
+    read_handle: &mut T,
+    lines: &mut Vec<String>,
+    _diagnostics: &mut Diagnostics,
+) -> Result<()> {{
+    for line in input::lines(read_handle) {{
+        lines.push(line?);
+    }}
+
+    Ok(())
+}}
+
+/// Part 1 and part 2 are still to be solved.
+pub struct Day{day:02};
+
+impl Solution for Day{day:02} {{
+    const DAY: u8 = {day};
+
+    type Input = Vec<String>;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse<T: BufRead>(mut handle: T, diagnostics: &mut Diagnostics) -> Result<Vec<String>> {{
+        let mut lines = Vec::new();
+        read_lines(&mut handle, &mut lines, diagnostics)?;
+        Ok(lines)
+    }}
+
+    fn part1(_lines: &Vec<String>) -> Result<String> {{
+        Err(Error::NoSolution("part 1 is not solved yet".to_string()))
+    }}
+
+    fn part2(_lines: &Vec<String>) -> Result<String> {{
+        Err(Error::NoSolution("part 2 is not solved yet".to_string()))
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    // Copy the example from the puzzle here
+    const EXAMPLE: &str = "";
+
+    #[test]
+    #[ignore]
+    fn test_day_{day:02}a_example_01() {{
+        let lines = Day{day:02}::parse(EXAMPLE.as_bytes(), &mut Diagnostics::strict()).unwrap();
+        assert_eq!(Day{day:02}::part1(&lines).unwrap(), "");
+    }}
+
+    #[test]
+    #[ignore]
+    fn test_day_{day:02}b_example_01() {{
+        let lines = Day{day:02}::parse(EXAMPLE.as_bytes(), &mut Diagnostics::strict()).unwrap();
+        assert_eq!(Day{day:02}::part2(&lines).unwrap(), "");
+    }}
+}}
+"#,
+        day = day
+    )
+}
+
+fn binary_template(day: u8, part: char) -> String {
+    let solve = if part == 'a' { "part1" } else { "part2" };
+    format!(
+        r#"extern crate advent_2018;
+
+use advent_2018::cli::DayOptions;
+use advent_2018::day_{day:02}::Day{day:02};
+use advent_2018::error::Diagnostics;
+use advent_2018::report::{{self, Format}};
+use advent_2018::solution::Solution;
+use advent_2018::trace;
+use std::io;
+
+/// Enter data to test via stdin, or a file, for this day's exercise.
+fn main() -> io::Result<()> {{
+    let options = DayOptions::from_env("day_{day:02}{part}");
+    let input_handle = options.open_input();
+
+    if options.format == Format::Json {{
+        report::print_json("{day}{part}", input_handle);
+        return Ok(());
+    }}
+
+    trace::info(format_args!("Day {day:02}: https://adventofcode.com/2018/day/{day}"));
+
+    // Bad lines are skipped, then listed once the input is done
+    let mut diagnostics = Diagnostics::lenient();
+    let result =
+        Day{day:02}::parse(input_handle, &mut diagnostics).and_then(|lines| Day{day:02}::{solve}(&lines));
+    println!("{{}}", diagnostics.finish(result));
+
+    Ok(())
+}}
+"#,
+        day = day,
+        part = part,
+        solve = solve
+    )
+}
+
+fn answers_template() -> &'static str {
+    "# Known correct answers for input.txt, checked by `aoc verify`\n"
+}
+
+/// Insert `text` after the last line starting with `prefix`.
+fn insert_after_last(source: &str, prefix: &str, text: &str) -> Option<String> {
+    let mut offset = 0;
+    let mut insert_at = None;
+    for line in source.split_inclusive('\n') {
+        offset += line.len();
+        if line.trim_start().starts_with(prefix) {
+            insert_at = Some(offset);
+        }
+    }
+
+    let insert_at = insert_at?;
+    Some(format!(
+        "{}{}{}",
+        &source[..insert_at],
+        text,
+        &source[insert_at..]
+    ))
+}
+
+/// Insert `text` before the `];` closing the array that `start` opens.
+fn insert_in_array(source: &str, start: &str, text: &str) -> Option<String> {
+    let start = source.find(start)?;
+    let end = start + source[start..].find("\n];")? + 1;
+    Some(format!("{}{}{}", &source[..end], text, &source[end..]))
+}
+
+fn register_module(lib: &str, day: u8) -> Option<String> {
+    insert_after_last(lib, "pub mod day_", &format!("pub mod day_{:02};\n", day))
+}
+
+fn register_import(source: &str, day: u8) -> Option<String> {
+    insert_after_last(
+        source,
+        "use day_",
+        &format!("use day_{day:02}::Day{day:02};\n", day = day),
+    )
+}
+
+fn register_puzzles(runner: &str, day: u8) -> Option<String> {
+    let entries = format!(
+        "    Puzzle {{
+        day: Day{day:02}::DAY,
+        part: 'a',
+        solve: solve_part1::<Day{day:02}>,
+    }},
+    Puzzle {{
+        day: Day{day:02}::DAY,
+        part: 'b',
+        solve: solve_part2::<Day{day:02}>,
+    }},
+",
+        day = day
+    );
+    insert_in_array(
+        &register_import(runner, day)?,
+        "pub const PUZZLES",
+        &entries,
+    )
+}
+
+fn register_bench(bench: &str, day: u8) -> Option<String> {
+    let entry = format!(
+        "    Bench {{
+        day: Day{day:02}::DAY,
+        run: bench_solution::<Day{day:02}>,
+    }},
+",
+        day = day
+    );
+    insert_in_array(&register_import(bench, day)?, "pub const BENCHES", &entry)
+}
+
+/// The README rebuilt with `day` added to the registered days.
+fn register_readme(text: &str, day: u8, root: &Path) -> Option<String> {
+    let mut days = readme::registered_days();
+    days.push((day, vec!['a', 'b']));
+    days.sort();
+    readme::generate(text, &days, root).ok()
+}
+
+type Change<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+/// What `change` would make of the file at `path`.
+fn changed(path: &Path, change: Change) -> Result<String> {
+    change(&fs::read_to_string(path)?).ok_or_else(|| {
+        Error::Scaffold(format!(
+            "could not find where to register the new day in {}",
+            path.display()
+        ))
+    })
+}
+
+fn write<C: AsRef<[u8]>>(path: &Path, contents: C) -> Result<()> {
+    fs::write(path, contents).map_err(|e| Error::write(path, e))
+}
+
+/// Set up `day` in the repository at `root`, giving back the files written
+/// or changed. Nothing is touched if the day already exists.
+pub fn scaffold(day: u8, root: &Path) -> Result<Vec<PathBuf>> {
+    let module = root.join(format!("src/day_{:02}.rs", day));
+    if module.exists() {
+        return Err(Error::Scaffold(format!(
+            "{} already exists",
+            module.display()
+        )));
+    }
+
+    // Check every file can be changed before writing any
+    let lib = root.join("src/lib.rs");
+    let runner = root.join("src/runner.rs");
+    let bench = root.join("src/bench.rs");
+    let readme = root.join("README.md");
+    let changes: [(&Path, Change); 4] = [
+        (&lib, &|source| register_module(source, day)),
+        (&runner, &|source| register_puzzles(source, day)),
+        (&bench, &|source| register_bench(source, day)),
+        (&readme, &|source| register_readme(source, day, root)),
+    ];
+    for (path, change) in &changes {
+        changed(path, *change)?;
+    }
+
+    let mut written = Vec::new();
+
+    write(&module, module_template(day))?;
+    written.push(module);
+
+    for part in &['a', 'b'] {
+        let binary = root.join(format!("src/bin/day_{:02}{}.rs", day, part));
+        write(&binary, binary_template(day, *part))?;
+        written.push(binary);
+    }
+
+    let input = root.join(format!("input/day_{:02}a", day));
+    fs::create_dir_all(&input).map_err(|e| Error::write(&input, e))?;
+    let answers = input.join("answers.txt");
+    if !answers.exists() {
+        write(&answers, answers_template())?;
+        written.push(answers);
+    }
+
+    for (path, change) in &changes {
+        write(path, changed(path, *change)?)?;
+        written.push(path.to_path_buf());
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_register_puzzles() {
+        let runner = "use day_03::Day03;\nuse error::Result;\n\npub const PUZZLES: &[Puzzle] = &[\n    Puzzle {\n        day: Day03::DAY,\n        part: 'b',\n        solve: solve_part2::<Day03>,\n    },\n];\n\nfn other() -> [u8; 0] {\n    [\n];\n}\n";
+        let registered = register_puzzles(runner, 4).unwrap();
+
+        assert!(registered.starts_with("use day_03::Day03;\nuse day_04::Day04;\nuse error"));
+        assert!(registered.contains(
+            "        solve: solve_part2::<Day03>,\n    },\n    Puzzle {\n        day: Day04::DAY,\n        part: 'a',"
+        ));
+        assert!(registered.contains("solve: solve_part2::<Day04>,\n    },\n];\n\nfn other"));
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let root = env::temp_dir().join(format!("advent_2018_scaffold_{}", ::std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src/bin")).unwrap();
+
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        for file in &["src/lib.rs", "src/runner.rs", "src/bench.rs", "README.md"] {
+            fs::copy(manifest_dir.join(file), root.join(file)).unwrap();
+        }
+
+        let written = scaffold(25, &root).unwrap();
+        assert_eq!(written.len(), 8);
+
+        let module = fs::read_to_string(root.join("src/day_25.rs")).unwrap();
+        assert!(module.contains("impl Solution for Day25 {\n    const DAY: u8 = 25;"));
+        let binary = fs::read_to_string(root.join("src/bin/day_25b.rs")).unwrap();
+        assert!(binary.contains("report::print_json(\"25b\", input_handle);"));
+        assert!(binary.contains(".and_then(|lines| Day25::part2(&lines));"));
+        assert!(root.join("input/day_25a/answers.txt").exists());
+
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(lib.contains("pub mod day_25;\n"));
+        let bench = fs::read_to_string(root.join("src/bench.rs")).unwrap();
+        assert!(bench.contains("run: bench_solution::<Day25>,"));
+        let readme = fs::read_to_string(root.join("README.md")).unwrap();
+        assert!(readme.contains("  * [ Exercise 2](#Day25b)\n<!-- end generated: contents -->"));
+        assert!(readme.contains("cargo run --bin day_25b -- < input/day_25a/input.txt\n"));
+
+        // A second go changes nothing
+        assert_eq!(
+            scaffold(25, &root).unwrap_err().to_string(),
+            format!(
+                "Setup failed: {} already exists",
+                root.join("src/day_25.rs").display()
+            )
+        );
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), lib);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,76 @@
+//! The shape every day's puzzle is solved in.
+//!
+//! Input is parsed once, then each part works from the parsed value. Runners,
+//! benchmarks and the like only need to know about this trait.
+
+use error::{Diagnostics, Result};
+use input::Replay;
+use std::fmt;
+use std::io::BufRead;
+use std::time::{Duration, Instant};
+
+/// A day's puzzle: parse the input once, then answer both parts from it.
+pub trait Solution {
+    /// Day of December the puzzle is for.
+    const DAY: u8;
+
+    /// What the input parses into, shared by both parts.
+    type Input;
+
+    /// Answers only need printing, so each part picks whatever type suits it.
+    type Answer1: fmt::Display;
+    type Answer2: fmt::Display;
+
+    /// Bad lines are reported to `diagnostics`, which decides whether to stop.
+    fn parse<T: BufRead>(handle: T, diagnostics: &mut Diagnostics) -> Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1>;
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2>;
+}
+
+/// A printed answer and how long it took to get.
+#[derive(Debug)]
+pub struct Solved {
+    pub answer: String,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+/// Parse with `S` and answer one part with `part`, timing both.
+fn solve<S, A, F>(handle: &mut dyn Replay, diagnostics: &mut Diagnostics, part: F) -> Result<Solved>
+where
+    S: Solution,
+    A: fmt::Display,
+    F: Fn(&S::Input) -> Result<A>,
+{
+    let start = Instant::now();
+    let input = S::parse(handle, diagnostics)?;
+    let parse_time = start.elapsed();
+
+    let start = Instant::now();
+    let answer = part(&input)?.to_string();
+    let solve_time = start.elapsed();
+
+    Ok(Solved {
+        answer,
+        parse_time,
+        solve_time,
+    })
+}
+
+/// Parse and solve part 1, for callers that just want the printed answer.
+pub fn solve_part1<S: Solution>(
+    handle: &mut dyn Replay,
+    diagnostics: &mut Diagnostics,
+) -> Result<Solved> {
+    solve::<S, _, _>(handle, diagnostics, S::part1)
+}
+
+/// Parse and solve part 2, for callers that just want the printed answer.
+pub fn solve_part2<S: Solution>(
+    handle: &mut dyn Replay,
+    diagnostics: &mut Diagnostics,
+) -> Result<Solved> {
+    solve::<S, _, _>(handle, diagnostics, S::part2)
+}
@@ -0,0 +1,563 @@
+//! Posting answers to the puzzle site and keeping track of what it said.
+//!
+//! Every reply is kept in a history file, one line per submission:
+//!
+//! ```text
+//! <unix time> <day> <part> <verdict> <seconds to wait> <answer>
+//! ```
+//!
+//! Before posting, the history is checked so that the same answer is never
+//! sent twice, answers already ruled out by an earlier "too high" or "too
+//! low" are not sent at all, and nothing is sent while the site has asked
+//! to wait.
+
+use error::{Error, Result};
+use fetch::Site;
+use http::{Client, Request};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The history is kept with the inputs, but not checked in.
+pub fn default_history_path() -> PathBuf {
+    PathBuf::from("input/submissions.txt")
+}
+
+/// What the site made of an answer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without saying which way.
+    Wrong,
+    /// Sent too soon after the last answer, so not looked at.
+    TooSoon,
+    /// The part was already solved, so not looked at.
+    AlreadySolved,
+    /// The reply did not say anything recognisable.
+    Unknown,
+}
+
+impl Verdict {
+    /// How the verdict is written in the history.
+    fn key(self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too_high",
+            Verdict::TooLow => "too_low",
+            Verdict::Wrong => "wrong",
+            Verdict::TooSoon => "too_soon",
+            Verdict::AlreadySolved => "already_solved",
+            Verdict::Unknown => "unknown",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Verdict> {
+        [
+            Verdict::Correct,
+            Verdict::TooHigh,
+            Verdict::TooLow,
+            Verdict::Wrong,
+            Verdict::TooSoon,
+            Verdict::AlreadySolved,
+            Verdict::Unknown,
+        ]
+        .iter()
+        .cloned()
+        .find(|verdict| verdict.key() == key)
+    }
+
+    /// Whether the answer was actually judged, rather than turned away.
+    pub fn is_judged(self) -> bool {
+        matches!(
+            self,
+            Verdict::Correct | Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong
+        )
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too high",
+            Verdict::TooLow => "too low",
+            Verdict::Wrong => "wrong",
+            Verdict::TooSoon => "too soon, not checked",
+            Verdict::AlreadySolved => "already solved, not checked",
+            Verdict::Unknown => "not understood",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// A verdict, and how long the site wants left before the next answer.
+#[derive(Debug, PartialEq)]
+pub struct Reply {
+    pub verdict: Verdict,
+    pub wait_seconds: u64,
+}
+
+/// The text of the reply's `<article>`, without any markup.
+fn article_text(html: &str) -> String {
+    let start = html.find("<article").unwrap_or(0);
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |end| start + end);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html[start..end].chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// A count of seconds from e.g. `1m 23s` or `37s`.
+fn parse_time_left(text: &str) -> Option<u64> {
+    let mut seconds = 0;
+    for part in text.split_whitespace() {
+        if let Some(minutes) = part.strip_suffix('m') {
+            seconds += minutes.parse::<u64>().ok()? * 60;
+        } else if let Some(part_seconds) = part.strip_suffix('s') {
+            seconds += part_seconds.parse::<u64>().ok()?;
+        } else {
+            return None;
+        }
+    }
+    Some(seconds)
+}
+
+/// A count of minutes written out, e.g. `one` or `5`.
+fn parse_minutes(word: &str) -> Option<u64> {
+    let words = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    ];
+    words
+        .iter()
+        .position(|number| *number == word)
+        .map(|minutes| minutes as u64)
+        .or_else(|| word.parse().ok())
+}
+
+/// Make sense of the page the site sends back after an answer.
+pub fn parse_reply(html: &str) -> Reply {
+    let text = article_text(html).to_lowercase();
+
+    let verdict = if text.contains("that's the right answer") {
+        Verdict::Correct
+    } else if text.contains("too high") {
+        Verdict::TooHigh
+    } else if text.contains("too low") {
+        Verdict::TooLow
+    } else if text.contains("that's not the right answer") {
+        Verdict::Wrong
+    } else if text.contains("answer too recently") {
+        Verdict::TooSoon
+    } else if text.contains("solving the right level") {
+        Verdict::AlreadySolved
+    } else {
+        Verdict::Unknown
+    };
+
+    // "You have 1m 23s left to wait" or "Please wait one minute"
+    let left = text.find(" left to wait").and_then(|end| {
+        let start = text[..end].rfind("you have ")? + "you have ".len();
+        parse_time_left(&text[start..end])
+    });
+    let please_wait = text.find("please wait ").and_then(|start| {
+        let rest = &text[start + "please wait ".len()..];
+        let mut words = rest.split_whitespace();
+        let minutes = parse_minutes(words.next()?)?;
+        match words.next() {
+            Some(unit) if unit.starts_with("minute") => Some(minutes * 60),
+            _ => None,
+        }
+    });
+
+    Reply {
+        verdict,
+        wait_seconds: left.or(please_wait).unwrap_or(0),
+    }
+}
+
+/// One line of the history.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// Seconds since the Unix epoch.
+    pub time: u64,
+    pub day: u8,
+    pub part: char,
+    pub verdict: Verdict,
+    pub wait_seconds: u64,
+    pub answer: String,
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.time,
+            self.day,
+            self.part,
+            self.verdict.key(),
+            self.wait_seconds,
+            self.answer
+        )
+    }
+}
+
+/// Read the history, which is empty if there is no file yet.
+pub fn read_history(path: &Path) -> Result<Vec<Entry>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut entries = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let bad = |message: &str| Error::parse(index + 1, 1, line, message);
+        let fields: Vec<&str> = line.splitn(6, ' ').collect();
+        if fields.len() != 6 {
+            return Err(bad("expected time, day, part, verdict, wait and answer"));
+        }
+
+        entries.push(Entry {
+            time: fields[0].parse().map_err(|_| bad("bad time"))?,
+            day: fields[1].parse().map_err(|_| bad("bad day"))?,
+            part: fields[2].chars().next().ok_or_else(|| bad("bad part"))?,
+            verdict: Verdict::from_key(fields[3]).ok_or_else(|| bad("unknown verdict"))?,
+            wait_seconds: fields[4].parse().map_err(|_| bad("bad wait"))?,
+            answer: fields[5].to_string(),
+        });
+    }
+
+    Ok(entries)
+}
+
+fn append_history(path: &Path, entry: &Entry) -> Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).map_err(|e| Error::write(path, e))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| Error::write(path, e))?;
+    writeln!(file, "{}", entry).map_err(|e| Error::write(path, e))
+}
+
+/// What came of trying to submit.
+#[derive(Debug, PartialEq)]
+pub enum Submission {
+    /// Sent, and this is what the site said.
+    Posted(Reply),
+    /// Not sent, the same answer was already given this verdict.
+    Duplicate(Verdict),
+    /// Not sent, earlier verdicts already rule it out.
+    RuledOut(String),
+    /// Not sent, the site asked to wait this many more seconds.
+    Waiting(u64),
+}
+
+impl fmt::Display for Submission {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Submission::Posted(reply) if reply.wait_seconds > 0 => write!(
+                f,
+                "{} (wait {}s before the next answer)",
+                reply.verdict, reply.wait_seconds
+            ),
+            Submission::Posted(reply) => write!(f, "{}", reply.verdict),
+            Submission::Duplicate(verdict) => {
+                write!(f, "already submitted, it was {}", verdict)
+            }
+            Submission::RuledOut(reason) => write!(f, "not submitted, {}", reason),
+            Submission::Waiting(seconds) => {
+                write!(f, "not submitted, wait another {}s", seconds)
+            }
+        }
+    }
+}
+
+/// Whether the history already says how `answer` would go, or that it
+/// should not be sent yet.
+pub fn check(history: &[Entry], day: u8, part: char, answer: &str, now: u64) -> Option<Submission> {
+    // The site's wait applies to every day
+    if let Some(last) = history.last() {
+        let until = last.time + last.wait_seconds;
+        if until > now {
+            return Some(Submission::Waiting(until - now));
+        }
+    }
+
+    let judged = history
+        .iter()
+        .filter(|entry| entry.day == day && entry.part == part && entry.verdict.is_judged());
+
+    for entry in judged {
+        if entry.answer == answer {
+            return Some(Submission::Duplicate(entry.verdict));
+        }
+
+        let reason = match (
+            entry.verdict,
+            answer.parse::<i64>(),
+            entry.answer.parse::<i64>(),
+        ) {
+            (Verdict::Correct, _, _) => format!("{} was already correct", entry.answer),
+            (Verdict::TooHigh, Ok(answer), Ok(high)) if answer >= high => {
+                format!("{} was already too high", high)
+            }
+            (Verdict::TooLow, Ok(answer), Ok(low)) if answer <= low => {
+                format!("{} was already too low", low)
+            }
+            _ => continue,
+        };
+        return Some(Submission::RuledOut(reason));
+    }
+
+    None
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or(0)
+}
+
+/// Submit `answer` for `day` and `part` (`a` or `b`) unless the history at
+/// `history_path` says not to, then note the reply there.
+pub fn submit(
+    day: u8,
+    part: char,
+    answer: &str,
+    site: &Site,
+    client: &dyn Client,
+    history_path: &Path,
+    now: u64,
+) -> Result<Submission> {
+    let history = read_history(history_path)?;
+    if let Some(submission) = check(&history, day, part, answer, now) {
+        return Ok(submission);
+    }
+
+    let url = format!("{}/answer", site.day_url(day));
+    let level = if part == 'a' { "1" } else { "2" };
+    let request = Request::post_form(&url, &[("level", level), ("answer", answer)]);
+    let response = client
+        .send(&site.request(request)?)
+        .map_err(|e| Error::Http(format!("{}: {}", url, e)))?;
+    if !response.is_success() {
+        return Err(Error::Http(format!(
+            "{} gave status {}",
+            url, response.status
+        )));
+    }
+
+    let reply = parse_reply(&response.body);
+    append_history(
+        history_path,
+        &Entry {
+            time: now,
+            day,
+            part,
+            verdict: reply.verdict,
+            wait_seconds: reply.wait_seconds,
+            answer: answer.to_string(),
+        },
+    )?;
+
+    Ok(Submission::Posted(reply))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::{test_server, TcpClient};
+    use std::env;
+
+    const TOO_HIGH: &str =
+        "<main><article><p>That's not the right answer; your answer is too high. \
+        If you're stuck, make sure you're using the full input data. Please wait one minute \
+        before trying again. <a href=\"/2018/day/1\">[Return to Day 1]</a></p></article></main>";
+    const CORRECT: &str = "<article><p>That's the right answer!  You are <em>one gold star</em> \
+        closer to fixing the time stream.</p></article>";
+    const TOO_SOON: &str = "<article><p>You gave an answer too recently; you have to wait after \
+        submitting an answer before trying again.  You have 1m 23s left to wait.</p></article>";
+
+    fn entry(time: u64, part: char, verdict: Verdict, wait_seconds: u64, answer: &str) -> Entry {
+        Entry {
+            time,
+            day: 1,
+            part,
+            verdict,
+            wait_seconds,
+            answer: answer.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        assert_eq!(
+            parse_reply(TOO_HIGH),
+            Reply {
+                verdict: Verdict::TooHigh,
+                wait_seconds: 60
+            }
+        );
+        assert_eq!(parse_reply(CORRECT).verdict, Verdict::Correct);
+        assert_eq!(
+            parse_reply(TOO_SOON),
+            Reply {
+                verdict: Verdict::TooSoon,
+                wait_seconds: 83
+            }
+        );
+        assert_eq!(
+            parse_reply("<article>your answer is too low. please wait 5 minutes</article>"),
+            Reply {
+                verdict: Verdict::TooLow,
+                wait_seconds: 300
+            }
+        );
+        assert_eq!(
+            parse_reply(
+                "<article>You don't seem to be solving the right level.  Did you already complete it?</article>"
+            )
+            .verdict,
+            Verdict::AlreadySolved
+        );
+        assert_eq!(parse_reply("<html>Oops</html>").verdict, Verdict::Unknown);
+    }
+
+    #[test]
+    fn test_check() {
+        let history = vec![
+            entry(100, 'a', Verdict::TooHigh, 60, "500"),
+            entry(200, 'a', Verdict::TooLow, 60, "300"),
+            entry(300, 'a', Verdict::TooSoon, 30, "400"),
+        ];
+
+        assert_eq!(
+            check(&history, 1, 'a', "450", 310),
+            Some(Submission::Waiting(20))
+        );
+        assert_eq!(
+            check(&history, 1, 'a', "500", 400),
+            Some(Submission::Duplicate(Verdict::TooHigh))
+        );
+        assert_eq!(
+            check(&history, 1, 'a', "600", 400),
+            Some(Submission::RuledOut("500 was already too high".to_string()))
+        );
+        assert_eq!(
+            check(&history, 1, 'a', "299", 400),
+            Some(Submission::RuledOut("300 was already too low".to_string()))
+        );
+
+        // Sent too soon, so never judged
+        assert_eq!(check(&history, 1, 'a', "400", 400), None);
+        assert_eq!(check(&history, 1, 'b', "500", 400), None);
+        assert_eq!(check(&history, 2, 'a', "500", 400), None);
+    }
+
+    #[test]
+    fn test_submit_and_history() {
+        let history_path = env::temp_dir().join(format!(
+            "advent_2018_submissions_{}.txt",
+            ::std::process::id()
+        ));
+        let _ = fs::remove_file(&history_path);
+
+        let (url, server) = test_server::serve(vec![(200, TOO_HIGH), (200, CORRECT)]);
+        let site = Site {
+            base_url: url,
+            session: Some("abc".to_string()),
+        };
+        let client = TcpClient::default();
+        let submit = |answer: &str, now: u64| {
+            submit(1, 'b', answer, &site, &client, &history_path, now).unwrap()
+        };
+
+        assert_eq!(
+            submit("500", 1000),
+            Submission::Posted(Reply {
+                verdict: Verdict::TooHigh,
+                wait_seconds: 60
+            })
+        );
+        assert_eq!(submit("400", 1030), Submission::Waiting(30));
+        assert_eq!(submit("500", 1100), Submission::Duplicate(Verdict::TooHigh));
+        assert_eq!(
+            submit("400", 1100),
+            Submission::Posted(Reply {
+                verdict: Verdict::Correct,
+                wait_seconds: 0
+            })
+        );
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2018/day/1/answer HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=abc\r\n"));
+        assert!(requests[0].ends_with("level=2&answer=500"));
+
+        assert_eq!(
+            read_history(&history_path).unwrap(),
+            vec![
+                entry(1000, 'b', Verdict::TooHigh, 60, "500"),
+                entry(1100, 'b', Verdict::Correct, 0, "400"),
+            ]
+        );
+        fs::remove_file(&history_path).unwrap();
+    }
+
+    #[test]
+    fn test_read_bad_history() {
+        let path = env::temp_dir().join(format!(
+            "advent_2018_bad_submissions_{}.txt",
+            ::std::process::id()
+        ));
+        fs::write(&path, "100 1 a too_high 60 500\n100 1 a maybe 0 7\n").unwrap();
+        assert_eq!(
+            read_history(&path).unwrap_err().to_string(),
+            "line 2, column 1: unknown verdict ('100 1 a maybe 0 7')"
+        );
+        fs::remove_file(&path).unwrap();
+
+        assert!(read_history(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unwritable_history() {
+        // A file where the history's directory should go
+        let blocker = env::temp_dir().join(format!(
+            "advent_2018_history_blocker_{}",
+            ::std::process::id()
+        ));
+        fs::write(&blocker, "").unwrap();
+        let path = blocker.join("submissions.txt");
+
+        let error = append_history(&path, &entry(100, 'a', Verdict::Correct, 0, "7")).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with(&format!("Failed to write {}: ", path.display())));
+        fs::remove_file(&blocker).unwrap();
+    }
+}
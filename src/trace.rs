@@ -0,0 +1,154 @@
+//! Output for following along, kept apart from the answers.
+//!
+//! Answers go to stdout. Everything else goes through here, to stderr unless
+//! a file is chosen, depending on the level: `quiet` shows nothing, `normal`
+//! shows notes like each day's header, and `trace` adds the step by step
+//! output of each day.
+//!
+//! Settings are per thread, which keeps tests apart. The binaries only have
+//! the one thread.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, LineWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Level {
+    Quiet,
+    Normal,
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(level: &str) -> Result<Level, String> {
+        match level {
+            "quiet" => Ok(Level::Quiet),
+            "normal" => Ok(Level::Normal),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!(
+                "Unknown verbosity '{}', try quiet, normal or trace",
+                level
+            )),
+        }
+    }
+}
+
+thread_local! {
+    static LEVEL: Cell<Level> = const { Cell::new(Level::Normal) };
+    // Stderr when there is nothing here
+    static OUTPUT: RefCell<Option<Box<dyn Write>>> = const { RefCell::new(None) };
+}
+
+pub fn set_level(level: Level) {
+    LEVEL.with(|current| current.set(level));
+}
+
+pub fn level() -> Level {
+    LEVEL.with(Cell::get)
+}
+
+/// Whether output at `level` would be shown. Check this first when the
+/// output is expensive to put together.
+pub fn enabled(level: Level) -> bool {
+    level != Level::Quiet && level <= self::level()
+}
+
+/// Send output somewhere other than stderr.
+pub fn set_output(output: Box<dyn Write>) {
+    OUTPUT.with(|current| *current.borrow_mut() = Some(output));
+}
+
+/// Send output to a file, replacing anything already in it.
+pub fn set_output_file(path: &Path) -> io::Result<()> {
+    set_output(Box::new(LineWriter::new(File::create(path)?)));
+    Ok(())
+}
+
+/// Send output back to stderr.
+pub fn reset_output() {
+    OUTPUT.with(|current| *current.borrow_mut() = None);
+}
+
+/// Failing to write is ignored, following along is not worth stopping for.
+fn write_line(level: Level, line: fmt::Arguments) {
+    if !enabled(level) {
+        return;
+    }
+
+    OUTPUT.with(|output| match *output.borrow_mut() {
+        Some(ref mut output) => {
+            let _ = writeln!(output, "{}", line);
+        }
+        None => {
+            let _ = writeln!(io::stderr().lock(), "{}", line);
+        }
+    });
+}
+
+/// A note shown unless asked to be quiet, e.g. `info(format_args!(...))`.
+pub fn info(line: fmt::Arguments) {
+    write_line(Level::Normal, line);
+}
+
+/// Step by step output, only shown at trace level.
+pub fn trace(line: fmt::Arguments) {
+    write_line(Level::Trace, line);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    /// Somewhere to write that the test can still read afterwards.
+    #[derive(Clone, Default)]
+    struct Shared(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(bytes)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn written(shared: &Shared) -> String {
+        String::from_utf8(shared.0.borrow().clone()).unwrap()
+    }
+
+    #[test]
+    fn test_levels() {
+        let shared = Shared::default();
+        set_output(Box::new(shared.clone()));
+
+        set_level(Level::Normal);
+        info(format_args!("header {}", 1));
+        trace(format_args!("step {}", 1));
+        assert_eq!(written(&shared), "header 1\n");
+
+        set_level(Level::Trace);
+        trace(format_args!("step {}", 2));
+        assert_eq!(written(&shared), "header 1\nstep 2\n");
+
+        set_level(Level::Quiet);
+        info(format_args!("header {}", 3));
+        assert!(!enabled(Level::Normal));
+        assert_eq!(written(&shared), "header 1\nstep 2\n");
+
+        reset_output();
+        set_level(Level::Normal);
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!("trace".parse::<Level>(), Ok(Level::Trace));
+        assert!("loud".parse::<Level>().is_err());
+    }
+}
@@ -0,0 +1,235 @@
+//! Checking solutions against the known correct answers for our inputs.
+//!
+//! Each day's input directory has an `answers.txt` next to `input.txt`, one
+//! `part = answer` per line, with `#` starting a comment line:
+//!
+//! ```text
+//! # Known correct answers for input.txt
+//! a = 402
+//! b = 481
+//! ```
+
+use error::{Diagnostics, Error, Result};
+use input;
+use runner::Puzzle;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, ErrorKind};
+use std::path::Path;
+
+/// Read `part = answer` lines into a map from part to answer.
+pub fn read_answers<T: BufRead>(handle: T) -> Result<HashMap<char, String>> {
+    let mut answers = HashMap::new();
+
+    for (index, line) in input::all_lines(handle).enumerate() {
+        let line = line?;
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.splitn(2, '=').map(str::trim);
+        let part = fields.next().unwrap_or_default();
+        let answer = fields.next().unwrap_or_default();
+
+        if part.len() != 1 || answer.is_empty() {
+            return Err(Error::parse(
+                index + 1,
+                1,
+                &line,
+                "expected 'part = answer', e.g. 'a = 402'",
+            ));
+        }
+
+        answers.insert(part.chars().next().unwrap(), answer.to_string());
+    }
+
+    Ok(answers)
+}
+
+/// How a puzzle's answer compared with the one on record.
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// No answer on record for this part yet.
+    Missing,
+    /// Solving or reading the answers went wrong.
+    Error(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Pass => write!(f, "pass"),
+            Outcome::Fail { expected } => write!(f, "FAIL (expected {})", expected),
+            Outcome::Missing => write!(f, "missing"),
+            Outcome::Error(message) => write!(f, "ERROR ({})", message),
+        }
+    }
+}
+
+/// The result of verifying one puzzle.
+pub struct Verification {
+    pub puzzle: &'static Puzzle,
+    pub answer: Option<String>,
+    pub outcome: Outcome,
+}
+
+impl Verification {
+    /// Passing and missing answers are fine, anything else needs looking at.
+    pub fn is_ok(&self) -> bool {
+        match self.outcome {
+            Outcome::Pass | Outcome::Missing => true,
+            Outcome::Fail { .. } | Outcome::Error(_) => false,
+        }
+    }
+}
+
+/// Known answers for a puzzle, or `None` if the day has no answers file.
+fn expected_answer(puzzle: &Puzzle, root: &Path) -> Result<Option<String>> {
+    let path = root.join(puzzle.default_answers_path());
+    let file = match File::open(&path) {
+        Ok(file) => file,
+        Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(read_answers(BufReader::new(file))?.remove(&puzzle.part))
+}
+
+/// Solve a puzzle on its checked in input under `root` and compare.
+pub fn verify(puzzle: &'static Puzzle, root: &Path) -> Verification {
+    let expected = match expected_answer(puzzle, root) {
+        Ok(expected) => expected,
+        Err(e) => {
+            return Verification {
+                puzzle,
+                answer: None,
+                outcome: Outcome::Error(format!("answers file: {}", e)),
+            }
+        }
+    };
+
+    // A day fresh from `aoc new` has neither input nor answers yet
+    let path = root.join(puzzle.default_input_path());
+    if expected.is_none() && !path.exists() {
+        return Verification {
+            puzzle,
+            answer: None,
+            outcome: Outcome::Missing,
+        };
+    }
+
+    // Known inputs are expected to be clean, so any bad line is an error
+    let answer = match puzzle.solve_file(&path, &mut Diagnostics::strict()) {
+        Ok(solved) => solved.answer,
+        Err(e) => {
+            return Verification {
+                puzzle,
+                answer: None,
+                outcome: Outcome::Error(e.to_string()),
+            }
+        }
+    };
+
+    let outcome = match expected {
+        None => Outcome::Missing,
+        Some(ref expected) if *expected == answer => Outcome::Pass,
+        Some(expected) => Outcome::Fail { expected },
+    };
+
+    Verification {
+        puzzle,
+        answer: Some(answer),
+        outcome,
+    }
+}
+
+/// Lay verifications out one per row under a header.
+pub fn format_table(verifications: &[Verification]) -> String {
+    let mut table = format!("{:<5}{:<6}{:<28}{}\n", "Day", "Part", "Answer", "Result");
+
+    for verification in verifications {
+        table += &format!(
+            "{:<5}{:<6}{:<28}{}\n",
+            format!("{:02}", verification.puzzle.day),
+            verification.puzzle.part,
+            verification.answer.as_ref().map_or("-", String::as_str),
+            verification.outcome
+        );
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runner::{self, select, PUZZLES};
+    use std::path::PathBuf;
+    use std::{env, fs, process};
+
+    fn manifest_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    }
+
+    #[test]
+    fn test_read_answers() {
+        let answers = read_answers("# comment\n\na = 402\nb=#1023\n".as_bytes()).unwrap();
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers[&'a'], "402");
+        assert_eq!(answers[&'b'], "#1023");
+    }
+
+    #[test]
+    fn test_read_answers_bad_line() {
+        let error = read_answers("a = 1\nb 2\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: expected 'part = answer', e.g. 'a = 402' ('b 2')"
+        );
+    }
+
+    #[test]
+    fn test_missing_input() {
+        // Nothing to check against yet
+        let verification = verify(select("1a").unwrap()[0], Path::new("/nonexistent"));
+        assert_eq!(verification.outcome, Outcome::Missing);
+        assert!(verification.is_ok());
+
+        // Answers without the input they are for can't be checked
+        let root = env::temp_dir().join(format!("advent_2018_verify_{}", process::id()));
+        let answers = root.join(runner::default_answers_path(1));
+        fs::create_dir_all(answers.parent().unwrap()).unwrap();
+        fs::write(&answers, "a = 402\n").unwrap();
+        let verification = verify(select("1a").unwrap()[0], &root);
+        fs::remove_dir_all(&root).unwrap();
+        match verification.outcome {
+            Outcome::Error(_) => {}
+            other => panic!("Expected the missing input to be an error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_verify_checked_in_answers() {
+        // Every registered puzzle must give the answer on record. Days
+        // fresh from `aoc new` have nothing on record until they are solved
+        for puzzle in PUZZLES {
+            if expected_answer(puzzle, &manifest_dir()).unwrap().is_none() {
+                continue;
+            }
+
+            let verification = verify(puzzle, &manifest_dir());
+            assert_eq!(
+                verification.outcome,
+                Outcome::Pass,
+                "{} gave {:?}",
+                puzzle.name(),
+                verification.answer
+            );
+        }
+    }
+}